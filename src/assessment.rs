@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{scenario::Scenario, ConformanceOutcome};
+
+/// Well-known path where a gateway may publish its self-declared conformance manifest.
+pub const SELF_ASSESSMENT_PATH: &str = "/.well-known/reclaw-conformance";
+
+/// What a gateway claims to pass, as published at [`SELF_ASSESSMENT_PATH`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SelfAssessment {
+    /// Scenario names the gateway claims to pass.
+    pub scenarios: Vec<String>,
+    /// Severity tiers (`required`, `recommended`) whose scenarios the gateway claims to pass.
+    pub tiers: Vec<String>,
+}
+
+/// Differences between a [`SelfAssessment`] and the observed outcomes, in suite order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Reconciliation {
+    /// Claimed scenarios that failed.
+    pub contradicted: Vec<String>,
+    /// Scenarios that passed without being claimed.
+    pub unclaimed_passes: Vec<String>,
+    /// Claimed scenarios that were skipped or not selected, so the claim was not tested.
+    pub unverified: Vec<String>,
+    /// Claimed scenario names or tiers the suite does not know.
+    pub unknown: Vec<String>,
+}
+
+impl SelfAssessment {
+    fn claims(&self, name: &str, severity: &str) -> bool {
+        self.scenarios.iter().any(|claim| claim == name)
+            || self.tiers.iter().any(|tier| tier == severity)
+    }
+
+    pub fn reconcile(&self, outcomes: &[ConformanceOutcome]) -> Reconciliation {
+        let mut reconciliation = Reconciliation::default();
+        for outcome in outcomes {
+            let claimed = self.claims(outcome.name, outcome.severity.as_str());
+            if outcome.skipped.is_some() {
+                if claimed {
+                    reconciliation.unverified.push(outcome.name.to_owned());
+                }
+            } else if outcome.passed && !claimed {
+                reconciliation
+                    .unclaimed_passes
+                    .push(outcome.name.to_owned());
+            } else if !outcome.passed && claimed {
+                reconciliation.contradicted.push(outcome.name.to_owned());
+            }
+        }
+
+        let ran = |name: &str| outcomes.iter().any(|outcome| outcome.name == name);
+        for scenario in Scenario::all() {
+            if !ran(scenario.name()) && self.claims(scenario.name(), scenario.severity().as_str()) {
+                reconciliation.unverified.push(scenario.name().to_owned());
+            }
+        }
+        for claim in &self.scenarios {
+            if !ran(claim) && Scenario::from_name(claim).is_none() {
+                reconciliation.unknown.push(claim.clone());
+            }
+        }
+        for tier in &self.tiers {
+            if !matches!(tier.as_str(), "required" | "recommended") {
+                reconciliation.unknown.push(format!("tier {tier}"));
+            }
+        }
+        reconciliation
+    }
+}
+
+impl Reconciliation {
+    /// Whether every claim matched an observed result and nothing passed unclaimed.
+    pub fn is_consistent(&self) -> bool {
+        self.sections().iter().all(|(_, names)| names.is_empty())
+    }
+
+    /// Report section headings paired with their entries.
+    pub fn sections(&self) -> [(&'static str, &[String]); 4] {
+        [
+            ("Contradicted claims", &self.contradicted),
+            ("Unclaimed passes", &self.unclaimed_passes),
+            ("Unverified claims", &self.unverified),
+            ("Unknown claims", &self.unknown),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConformanceOutcome, Severity, SkipReason};
+
+    use super::{Reconciliation, SelfAssessment};
+
+    #[test]
+    fn reconcile_flags_contradicted_and_unclaimed_results() {
+        let assessment: SelfAssessment = serde_json::from_value(serde_json::json!({
+            "scenarios": ["healthz.ok_true", "readyz.ok_true", "ws.not_a_scenario"],
+            "tiers": ["recommended", "gold"]
+        }))
+        .expect("manifest should parse");
+        let mut head = ConformanceOutcome::fail("healthz.head_no_body", "body present");
+        head.severity = Severity::Recommended;
+        let outcomes = vec![
+            ConformanceOutcome::pass("healthz.ok_true", "ok"),
+            head,
+            ConformanceOutcome::fail("readyz.ok_true", "not ready"),
+            ConformanceOutcome::pass("info.protocol_version", "3"),
+            ConformanceOutcome::skip(
+                "healthz.dual_stack_reachable",
+                SkipReason::NotConfigured,
+                "off",
+            ),
+        ];
+
+        let reconciliation = assessment.reconcile(&outcomes);
+
+        assert_eq!(
+            reconciliation,
+            Reconciliation {
+                contradicted: vec![
+                    "healthz.head_no_body".to_owned(),
+                    "readyz.ok_true".to_owned()
+                ],
+                unclaimed_passes: vec!["info.protocol_version".to_owned()],
+                unverified: vec!["healthz.date_within_clock_skew".to_owned()],
+                unknown: vec!["ws.not_a_scenario".to_owned(), "tier gold".to_owned()],
+            }
+        );
+        assert!(!reconciliation.is_consistent());
+    }
+}
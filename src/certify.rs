@@ -0,0 +1,458 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    recorder::truncation_marker, scenario::Scenario, ConformanceOutcome, ConformanceReport,
+    ConformanceTransport, ScenarioTranscript, TranscriptEntry,
+};
+
+/// Bundles a run's evidence into a single archive for release approvals.
+pub struct CertificationPackage<'a> {
+    report: &'a ConformanceReport,
+    target: Value,
+    signing_key: Option<Vec<u8>>,
+    max_transcript_bytes: Option<u64>,
+}
+
+impl<'a> CertificationPackage<'a> {
+    pub fn new(report: &'a ConformanceReport, target: Value) -> Self {
+        Self {
+            report,
+            target,
+            signing_key: None,
+            max_transcript_bytes: None,
+        }
+    }
+
+    pub fn with_signing_key(mut self, key: Vec<u8>) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+
+    /// Caps the combined size of all transcript files. Oversized transcripts have payloads
+    /// replaced by truncation markers and, if still too large, their oldest entries rotated out.
+    pub fn with_max_transcript_bytes(mut self, limit: u64) -> Self {
+        self.max_transcript_bytes = Some(limit);
+        self
+    }
+
+    pub fn entries(&self) -> Result<Vec<(String, Vec<u8>)>, CertifyError> {
+        let report_json = to_json(self.report)?;
+        let report_digest = hex(&Sha256::digest(&report_json));
+
+        let mut entries = vec![
+            ("report.json".to_owned(), report_json.clone()),
+            (
+                "report.json.sha256".to_owned(),
+                format!("{report_digest}  report.json\n").into_bytes(),
+            ),
+        ];
+        if let Some(key) = &self.signing_key {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key)
+                .map_err(|error| CertifyError::Sign(error.to_string()))?;
+            mac.update(&report_json);
+            let signature = hex(&mac.finalize().into_bytes());
+            entries.push((
+                "report.json.sig".to_owned(),
+                format!("hmac-sha256 {signature}\n").into_bytes(),
+            ));
+        }
+        entries.push(("report.html".to_owned(), self.report.to_html().into_bytes()));
+        entries.push((
+            "report.md".to_owned(),
+            self.report.to_markdown().into_bytes(),
+        ));
+        entries.push(("target.json".to_owned(), to_json(&self.target)?));
+        entries.push((
+            "scenarios.json".to_owned(),
+            to_json(&Scenario::catalogue())?,
+        ));
+        entries.push(("summary.json".to_owned(), to_json(&self.summary())?));
+        let mut truncated = BTreeMap::new();
+        for (transcript, (bytes, original_bytes)) in self.report.transcripts.iter().zip(
+            bounded_transcripts(&self.report.transcripts, self.max_transcript_bytes)?,
+        ) {
+            let path = format!("transcripts/{}.json", transcript.scenario);
+            if let Some(original_bytes) = original_bytes {
+                truncated.insert(path.clone(), original_bytes);
+            }
+            entries.push((path, bytes));
+        }
+
+        let manifest = entries
+            .iter()
+            .map(|(path, bytes)| {
+                let mut entry = serde_json::json!({
+                    "path": path,
+                    "sha256": hex(&Sha256::digest(bytes)),
+                });
+                if let Some(original_bytes) = truncated.get(path) {
+                    entry["truncatedFromBytes"] = Value::from(*original_bytes);
+                }
+                entry
+            })
+            .collect::<Vec<_>>();
+        entries.push((
+            "manifest.json".to_owned(),
+            to_json(&serde_json::json!({ "files": manifest }))?,
+        ));
+
+        Ok(entries)
+    }
+
+    pub fn write_zip(&self, path: impl AsRef<Path>) -> Result<Vec<String>, CertifyError> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|error| CertifyError::Io(format!("{}: {error}", path.display())))?;
+        let mut archive = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+
+        let mut names = Vec::new();
+        for (name, bytes) in self.entries()? {
+            archive
+                .start_file(name.as_str(), options)
+                .map_err(|error| CertifyError::Archive(error.to_string()))?;
+            archive
+                .write_all(&bytes)
+                .map_err(|error| CertifyError::Io(error.to_string()))?;
+            names.push(name);
+        }
+        archive
+            .finish()
+            .map_err(|error| CertifyError::Archive(error.to_string()))?;
+
+        Ok(names)
+    }
+
+    fn summary(&self) -> Value {
+        let mut by_tag = BTreeMap::<&str, OutcomeCounts>::new();
+        let mut by_severity = BTreeMap::<&str, OutcomeCounts>::new();
+        for descriptor in Scenario::catalogue() {
+            let Some(outcome) = self
+                .report
+                .outcomes
+                .iter()
+                .find(|outcome| outcome.name == descriptor.name)
+            else {
+                continue;
+            };
+            by_severity
+                .entry(descriptor.severity.as_str())
+                .or_default()
+                .count(outcome);
+            for tag in descriptor.tags {
+                by_tag.entry(tag).or_default().count(outcome);
+            }
+        }
+
+        serde_json::json!({
+            "total": self.report.total,
+            "failed": self.report.failed,
+            "skipped": self.report.skipped,
+            "passing": self.report.is_passing(),
+            "byTag": by_tag,
+            "bySeverity": by_severity,
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+struct OutcomeCounts {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+impl OutcomeCounts {
+    fn count(&mut self, outcome: &ConformanceOutcome) {
+        if outcome.skipped.is_some() {
+            self.skipped += 1;
+        } else if outcome.passed {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+}
+
+pub fn target_metadata<T: ConformanceTransport>(transport: &T, base_url: &str) -> Value {
+    let generated_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_millis())
+        .unwrap_or(0);
+    let (info, info_error) = match transport.get_json("/info") {
+        Ok(info) => (info, Value::Null),
+        Err(error) => (Value::Null, Value::String(error.to_string())),
+    };
+
+    serde_json::json!({
+        "baseUrl": base_url,
+        "generatedAtMs": generated_at_ms,
+        "runnerVersion": env!("CARGO_PKG_VERSION"),
+        "info": info,
+        "infoError": info_error,
+    })
+}
+
+/// Encoded transcript file and, when it was truncated, its original size.
+type BoundedTranscript = (Vec<u8>, Option<u64>);
+
+/// Encodes every transcript, fitting them into `limit` bytes in total when set. Smaller
+/// transcripts are kept whole first and their unused share passes to the larger ones.
+///
+/// This is the second of two bounds. While capturing, each scenario already keeps at most an
+/// even share of the limit (`ConformanceRunner::with_max_capture_bytes`), keeping earlier
+/// payloads over later ones. Reports are never bounded; `manifest.json` records each
+/// truncated transcript's `truncatedFromBytes`.
+fn bounded_transcripts(
+    transcripts: &[ScenarioTranscript],
+    limit: Option<u64>,
+) -> Result<Vec<BoundedTranscript>, CertifyError> {
+    let encoded = transcripts
+        .iter()
+        .map(to_json)
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(limit) = limit else {
+        return Ok(encoded.into_iter().map(|bytes| (bytes, None)).collect());
+    };
+
+    let mut order = (0..encoded.len()).collect::<Vec<_>>();
+    order.sort_by_key(|index| encoded[*index].len());
+    let mut bounded = vec![(Vec::new(), None); encoded.len()];
+    let mut remaining = limit;
+    for (position, index) in order.into_iter().enumerate() {
+        let share = remaining / (encoded.len() - position) as u64;
+        let original_bytes = encoded[index].len() as u64;
+        let entry = if original_bytes <= share {
+            (encoded[index].clone(), None)
+        } else {
+            (
+                truncate_transcript(&transcripts[index], share)?,
+                Some(original_bytes),
+            )
+        };
+        remaining = remaining.saturating_sub(entry.0.len() as u64);
+        bounded[index] = entry;
+    }
+    Ok(bounded)
+}
+
+/// Shrinks one transcript towards `budget` bytes: the largest request and response payloads
+/// become `{ "truncated": true, "originalBytes": …, "sha256": … }` markers first, then the
+/// oldest entries are dropped behind a leading `truncated` entry with a `droppedEntries` count.
+fn truncate_transcript(
+    transcript: &ScenarioTranscript,
+    budget: u64,
+) -> Result<Vec<u8>, CertifyError> {
+    let mut transcript = transcript.clone();
+    let mut payloads = Vec::new();
+    for (index, entry) in transcript.entries.iter().enumerate() {
+        if let Some(request) = &entry.request {
+            payloads.push((json_len(request), index, false));
+        }
+        if let Some(response) = &entry.response {
+            payloads.push((json_len(response), index, true));
+        }
+    }
+    payloads.sort_by_key(|(bytes, _, _)| Reverse(*bytes));
+
+    let mut bytes = to_json(&transcript)?;
+    for (_, index, is_response) in payloads {
+        if bytes.len() as u64 <= budget {
+            return Ok(bytes);
+        }
+        let entry = &mut transcript.entries[index];
+        let payload = if is_response {
+            &mut entry.response
+        } else {
+            &mut entry.request
+        };
+        *payload = payload.as_ref().map(truncation_marker);
+        bytes = to_json(&transcript)?;
+    }
+
+    for dropped in 1..=transcript.entries.len() {
+        if bytes.len() as u64 <= budget {
+            break;
+        }
+        let mut entries = vec![TranscriptEntry {
+            operation: "truncated",
+            path: String::new(),
+            request: None,
+            status: None,
+            response: Some(serde_json::json!({ "droppedEntries": dropped })),
+            error: None,
+        }];
+        entries.extend_from_slice(&transcript.entries[dropped..]);
+        bytes = to_json(&ScenarioTranscript {
+            scenario: transcript.scenario,
+            entries,
+        })?;
+    }
+    Ok(bytes)
+}
+
+fn json_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+fn to_json<S: Serialize>(value: &S) -> Result<Vec<u8>, CertifyError> {
+    serde_json::to_vec_pretty(value).map_err(|error| CertifyError::Encode(error.to_string()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[derive(Debug, Error)]
+pub enum CertifyError {
+    #[error("failed to encode package entry: {0}")]
+    Encode(String),
+
+    #[error("failed to sign report: {0}")]
+    Sign(String),
+
+    #[error("failed to write package: {0}")]
+    Io(String),
+
+    #[error("failed to build package archive: {0}")]
+    Archive(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::{ConformanceOutcome, ConformanceReport, ScenarioTranscript, TranscriptEntry};
+
+    use super::CertificationPackage;
+
+    #[test]
+    fn entries_include_signature_manifest_and_transcripts() {
+        let mut report = ConformanceReport::new(vec![ConformanceOutcome::pass(
+            "healthz.ok_true",
+            "health endpoint returned ok=true",
+        )]);
+        report.transcripts.push(ScenarioTranscript {
+            scenario: "healthz.ok_true",
+            entries: Vec::new(),
+        });
+
+        let entries = CertificationPackage::new(&report, json!({ "baseUrl": "http://gw" }))
+            .with_signing_key(b"secret".to_vec())
+            .entries()
+            .expect("package entries should build");
+        let names = entries
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                "report.json",
+                "report.json.sha256",
+                "report.json.sig",
+                "report.html",
+                "report.md",
+                "target.json",
+                "scenarios.json",
+                "summary.json",
+                "transcripts/healthz.ok_true.json",
+                "manifest.json",
+            ]
+        );
+        let summary: serde_json::Value =
+            serde_json::from_slice(&entries[7].1).expect("summary should be JSON");
+        assert_eq!(summary["byTag"]["health"]["passed"], 1);
+        assert_eq!(summary["bySeverity"]["required"]["passed"], 1);
+    }
+
+    #[test]
+    fn entries_truncate_transcripts_beyond_the_size_limit() {
+        let entry = |response: Value| TranscriptEntry {
+            operation: "get",
+            path: "/info".to_owned(),
+            request: None,
+            status: None,
+            response: Some(response),
+            error: None,
+        };
+        let mut report = ConformanceReport::new(Vec::new());
+        report.transcripts = vec![
+            ScenarioTranscript {
+                scenario: "healthz.ok_true",
+                entries: vec![entry(json!({ "ok": true }))],
+            },
+            ScenarioTranscript {
+                scenario: "info.protocol_version",
+                entries: vec![
+                    entry(json!({ "blob": "x".repeat(200_000) })),
+                    entry(json!({ "protocolVersion": 3 })),
+                ],
+            },
+            ScenarioTranscript {
+                scenario: "readyz.ok_true",
+                entries: (0..200).map(|_| entry(json!({ "ok": true }))).collect(),
+            },
+        ];
+
+        let entries = CertificationPackage::new(&report, json!({}))
+            .with_max_transcript_bytes(8_000)
+            .entries()
+            .expect("package entries should build");
+        let entry_of = |name: &str| {
+            let (_, bytes) = entries
+                .iter()
+                .find(|(path, _)| path == name)
+                .expect("entry should exist");
+            serde_json::from_slice::<Value>(bytes).expect("entry should be JSON")
+        };
+        let transcript_bytes = entries
+            .iter()
+            .filter(|(path, _)| path.starts_with("transcripts/"))
+            .map(|(_, bytes)| bytes.len())
+            .sum::<usize>();
+        assert!(transcript_bytes <= 8_000, "{transcript_bytes}");
+
+        assert_eq!(
+            entry_of("transcripts/healthz.ok_true.json")["entries"][0]["response"],
+            json!({ "ok": true })
+        );
+        let info = entry_of("transcripts/info.protocol_version.json");
+        assert_eq!(info["entries"][0]["response"]["truncated"], true);
+        assert_eq!(info["entries"][1]["response"]["protocolVersion"], 3);
+        let readyz = entry_of("transcripts/readyz.ok_true.json");
+        assert_eq!(readyz["entries"][0]["operation"], "truncated");
+        assert!(readyz["entries"][0]["response"]["droppedEntries"].as_u64() > Some(0));
+
+        let manifest = entry_of("manifest.json");
+        let truncated = manifest["files"]
+            .as_array()
+            .expect("manifest files")
+            .iter()
+            .filter(|file| file.get("truncatedFromBytes").is_some())
+            .map(|file| file["path"].as_str().expect("path"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            truncated,
+            vec![
+                "transcripts/info.protocol_version.json",
+                "transcripts/readyz.ok_true.json"
+            ]
+        );
+    }
+}
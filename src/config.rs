@@ -0,0 +1,449 @@
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{Matcher, EXPECTED_PROTOCOL_VERSION};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConformanceConfig {
+    /// Scenario tag to owning team, used to route failing outcomes during triage.
+    pub owners: BTreeMap<String, String>,
+    /// Scenario names that are skipped until the underlying issue is fixed.
+    pub quarantine: Vec<String>,
+    /// Optional scenario groups the target claims to support, such as `v2-compat`.
+    pub profiles: Vec<String>,
+    pub scenario: ScenarioConfig,
+    pub limits: LimitsConfig,
+    pub trace: TraceConfig,
+}
+
+impl ConformanceConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|error| ConfigError::Read(format!("{}: {error}", path.display())))?;
+        Self::from_toml(&text)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|error| ConfigError::Parse(error.to_string()))
+    }
+
+    pub fn owner_for(&self, tags: &[&str]) -> Option<&str> {
+        tags.iter()
+            .find_map(|tag| self.owners.get(*tag))
+            .map(String::as_str)
+    }
+
+    pub fn is_quarantined(&self, scenario: &str) -> bool {
+        self.quarantine.iter().any(|name| name == scenario)
+    }
+
+    pub fn has_profile(&self, profile: &str) -> bool {
+        self.profiles.iter().any(|name| name == profile)
+    }
+
+    /// Longest a single WS read may block: the longest `agent.wait` the suite sends plus the
+    /// late tolerance of the timing band.
+    pub fn ws_read_timeout(&self) -> Duration {
+        let wait = &self.scenario.wait;
+        let longest_ms = wait.strategy.budget_ms().max(wait.probe_timeout_ms);
+        Duration::from_millis(longest_ms.saturating_add(self.scenario.timing.late_ms))
+    }
+}
+
+/// Tunables used while building scenario requests and judging responses.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioConfig {
+    /// `minProtocol` advertised in the WS `connect` frame.
+    pub min_protocol: u64,
+    /// `maxProtocol` advertised in the WS `connect` frame.
+    pub max_protocol: u64,
+    /// `protocolVersion` that `/info` must report.
+    pub expected_protocol_version: u64,
+    /// Opt in to `healthz.dual_stack_reachable`, which probes every address the target host
+    /// resolves to.
+    pub dual_stack: bool,
+    /// Largest tolerated difference between the gateway clock and the runner clock.
+    pub max_clock_skew_ms: u64,
+    /// How long a `channels.logout` may take to become visible on a second connection.
+    pub propagation_tolerance_ms: u64,
+    /// Interval between `channels.status` polls while waiting for it.
+    pub propagation_poll_ms: u64,
+    /// Matcher for `result.output` of completed runs, for agents whose output is not the
+    /// reference gateway's echo.
+    pub output: Option<Matcher>,
+    pub wait: WaitConfig,
+    pub timing: TimingConfig,
+    pub restart: RestartConfig,
+    pub chat_send: ChatSendConfig,
+}
+
+impl Default for ScenarioConfig {
+    fn default() -> Self {
+        Self {
+            min_protocol: 1,
+            max_protocol: EXPECTED_PROTOCOL_VERSION,
+            expected_protocol_version: EXPECTED_PROTOCOL_VERSION,
+            dual_stack: false,
+            max_clock_skew_ms: 5000,
+            propagation_tolerance_ms: 1000,
+            propagation_poll_ms: 100,
+            output: None,
+            wait: WaitConfig::default(),
+            timing: TimingConfig::default(),
+            restart: RestartConfig::default(),
+            chat_send: ChatSendConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaitConfig {
+    /// How `agent.wait` is polled while a run is expected to reach a terminal state.
+    pub strategy: WaitStrategy,
+    /// `timeoutMs` used when a scenario expects `agent.wait` to time out.
+    pub probe_timeout_ms: u64,
+}
+
+impl Default for WaitConfig {
+    fn default() -> Self {
+        Self {
+            strategy: WaitStrategy::default(),
+            probe_timeout_ms: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WaitStrategy {
+    Fixed {
+        timeout_ms: u64,
+        attempts: u32,
+    },
+    Exponential {
+        initial_ms: u64,
+        factor: u32,
+        max_ms: u64,
+        attempts: u32,
+    },
+    Deadline {
+        deadline_ms: u64,
+        slice_ms: u64,
+    },
+}
+
+impl Default for WaitStrategy {
+    fn default() -> Self {
+        Self::Fixed {
+            timeout_ms: 2000,
+            attempts: 1,
+        }
+    }
+}
+
+impl WaitStrategy {
+    /// Most `agent.wait` polls a single wait may make, whatever the strategy says.
+    pub const MAX_POLLS: u32 = 1000;
+
+    /// `timeoutMs` for each successive `agent.wait` poll; always between one and
+    /// [`Self::MAX_POLLS`] entries. A deadline whose slices would exceed the cap is cut into
+    /// proportionally longer slices.
+    pub fn schedule(&self) -> Vec<u64> {
+        match *self {
+            Self::Fixed {
+                timeout_ms,
+                attempts,
+            } => vec![timeout_ms; attempts.clamp(1, Self::MAX_POLLS) as usize],
+            Self::Exponential {
+                initial_ms,
+                factor,
+                max_ms,
+                attempts,
+            } => {
+                let mut timeout_ms = initial_ms.min(max_ms);
+                let attempts = attempts.clamp(1, Self::MAX_POLLS);
+                let mut schedule = Vec::with_capacity(attempts as usize);
+                for _ in 0..attempts {
+                    schedule.push(timeout_ms);
+                    timeout_ms = timeout_ms.saturating_mul(u64::from(factor)).min(max_ms);
+                }
+                schedule
+            }
+            Self::Deadline {
+                deadline_ms,
+                slice_ms,
+            } => {
+                let slice_ms = slice_ms
+                    .max(1)
+                    .max(deadline_ms.div_ceil(u64::from(Self::MAX_POLLS)));
+                let mut remaining = deadline_ms;
+                let mut schedule = vec![slice_ms.min(remaining)];
+                remaining -= schedule[0];
+                while remaining > 0 {
+                    let timeout_ms = slice_ms.min(remaining);
+                    schedule.push(timeout_ms);
+                    remaining -= timeout_ms;
+                }
+                schedule
+            }
+        }
+    }
+
+    /// Total time a single uninterrupted wait may take under this strategy.
+    pub fn budget_ms(&self) -> u64 {
+        self.schedule().iter().sum()
+    }
+}
+
+/// Tolerance band around the expected duration of timed responses, such as `agent.wait`
+/// timeouts. Durations are measured with the runner's monotonic clock.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimingConfig {
+    /// How much sooner than expected a timed response may arrive.
+    pub early_ms: u64,
+    /// How much later than expected a timed response may arrive, including connection setup.
+    pub late_ms: u64,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            early_ms: 10,
+            late_ms: 1000,
+        }
+    }
+}
+
+impl TimingConfig {
+    /// Judges `measured_ms` against `expected_ms`. Both arms describe the measurement; `Ok` is
+    /// marked borderline when it lies in the outer half of the band.
+    pub fn judge(&self, expected_ms: u64, measured_ms: u64) -> Result<String, String> {
+        let min = expected_ms.saturating_sub(self.early_ms);
+        let max = expected_ms.saturating_add(self.late_ms);
+        let measurement = format!("{measured_ms}ms for expected {expected_ms}ms");
+        if measured_ms < min {
+            Err(format!("{measurement}, early of band [{min}, {max}]ms"))
+        } else if measured_ms > max {
+            Err(format!("{measurement}, late of band [{min}, {max}]ms"))
+        } else if measured_ms < expected_ms.saturating_sub(self.early_ms / 2)
+            || measured_ms > expected_ms.saturating_add(self.late_ms / 2)
+        {
+            Ok(format!(
+                "{measurement}, borderline within band [{min}, {max}]ms"
+            ))
+        } else {
+            Ok(format!("{measurement}, within band [{min}, {max}]ms"))
+        }
+    }
+}
+
+/// Gateway restart used by `restart.recovers_health_and_runs`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RestartConfig {
+    /// Program and arguments that restart the gateway; the scenario is skipped when empty.
+    pub hook: Vec<String>,
+    /// How long the hook may run before it is killed and the scenario fails.
+    pub hook_timeout_ms: u64,
+    /// How long `/healthz` and `/readyz` may take to recover once the hook returns.
+    pub recovery_deadline_ms: u64,
+    pub poll_ms: u64,
+    /// Whether completed run results are expected to survive a restart. Set to `false` for
+    /// gateways that document in-memory run state.
+    pub runs_persist: bool,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            hook: Vec::new(),
+            hook_timeout_ms: 120_000,
+            recovery_deadline_ms: 30_000,
+            poll_ms: 500,
+            runs_persist: true,
+        }
+    }
+}
+
+/// Documented `chat.send` behaviour for each form of absent `message`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChatSendConfig {
+    /// `message` omitted.
+    pub missing: MessageExpectation,
+    /// `message` is `""`.
+    pub empty: MessageExpectation,
+    /// `message` is `null`.
+    pub null: MessageExpectation,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageExpectation {
+    /// `INVALID_REQUEST` naming `message`.
+    #[default]
+    Reject,
+    /// The run is queued like any other `chat.send`.
+    Accept,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// Responses whose JSON payload exceeds this many bytes are reported as warnings.
+    pub max_response_bytes: u64,
+    /// Payload bytes one scenario may keep for its transcript and the identifier audit; later
+    /// payloads are kept as truncation markers.
+    pub max_capture_bytes: u64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_response_bytes: 1024 * 1024,
+            max_capture_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// W3C trace context attached to outgoing requests.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraceConfig {
+    pub enabled: bool,
+    /// `traceparent` of the runner's own span; requests continue its trace when set.
+    pub parent: Option<String>,
+    pub tracestate: Option<String>,
+    /// Also add a `trace` field to WS frames, for gateway profiles that accept unknown fields.
+    pub ws_frames: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config: {0}")]
+    Read(String),
+
+    #[error("invalid config: {0}")]
+    Parse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{ConformanceConfig, TimingConfig, WaitStrategy};
+
+    #[test]
+    fn owner_for_prefers_most_specific_tag() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [owners]
+            ws = "realtime"
+            chat = "conversations"
+            "#,
+        )
+        .expect("config should parse");
+
+        assert_eq!(config.owner_for(&["chat", "ws"]), Some("conversations"));
+        assert_eq!(config.owner_for(&["agent", "ws"]), Some("realtime"));
+        assert_eq!(config.owner_for(&["tools", "http"]), None);
+    }
+
+    #[test]
+    fn from_toml_rejects_unknown_keys() {
+        let error = ConformanceConfig::from_toml("owner = 1").expect_err("should fail");
+        assert!(error.to_string().starts_with("invalid config:"));
+    }
+
+    #[test]
+    fn scenario_config_overrides_defaults_and_wait_schedules() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            max_protocol = 4
+
+            [scenario.wait]
+            probe_timeout_ms = 200
+
+            [scenario.wait.strategy]
+            kind = "exponential"
+            initial_ms = 250
+            factor = 2
+            max_ms = 1500
+            attempts = 5
+            "#,
+        )
+        .expect("config should parse");
+
+        assert_eq!(config.scenario.min_protocol, 1);
+        assert_eq!(config.scenario.max_protocol, 4);
+        assert_eq!(config.scenario.wait.probe_timeout_ms, 200);
+        assert_eq!(
+            config.scenario.wait.strategy.schedule(),
+            vec![250, 500, 1000, 1500, 1500]
+        );
+        assert_eq!(
+            WaitStrategy::Deadline {
+                deadline_ms: 5000,
+                slice_ms: 2000
+            }
+            .schedule(),
+            vec![2000, 2000, 1000]
+        );
+        assert_eq!(WaitStrategy::default().schedule(), vec![2000]);
+        assert_eq!(config.scenario.timing.late_ms, 1000);
+        assert_eq!(
+            WaitStrategy::Fixed {
+                timeout_ms: 100,
+                attempts: 0
+            }
+            .schedule(),
+            vec![100]
+        );
+        let capped = WaitStrategy::Deadline {
+            deadline_ms: u64::MAX,
+            slice_ms: 1,
+        }
+        .schedule();
+        assert_eq!(capped.len(), WaitStrategy::MAX_POLLS as usize);
+        assert_eq!(
+            WaitStrategy::Fixed {
+                timeout_ms: 1,
+                attempts: u32::MAX
+            }
+            .schedule()
+            .len(),
+            WaitStrategy::MAX_POLLS as usize
+        );
+    }
+
+    #[test]
+    fn timing_judge_reports_measurements_against_band() {
+        let timing = TimingConfig {
+            early_ms: 20,
+            late_ms: 400,
+        };
+
+        assert_eq!(
+            timing.judge(500, 530),
+            Ok("530ms for expected 500ms, within band [480, 900]ms".to_owned())
+        );
+        assert_eq!(
+            timing.judge(500, 850),
+            Ok("850ms for expected 500ms, borderline within band [480, 900]ms".to_owned())
+        );
+        assert_eq!(
+            timing.judge(500, 3),
+            Err("3ms for expected 500ms, early of band [480, 900]ms".to_owned())
+        );
+        assert_eq!(
+            timing.judge(500, 901),
+            Err("901ms for expected 500ms, late of band [480, 900]ms".to_owned())
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    cell::{Cell, RefCell},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Value;
+
+use crate::{config::ScenarioConfig, ConformanceConfig, Matcher};
+
+/// State shared by every scenario in a single run.
+pub(crate) struct RunContext {
+    scenario: ScenarioConfig,
+    nonce: RefCell<String>,
+    current: Cell<&'static str>,
+    ids: RefCell<Vec<(&'static str, String)>>,
+    echoes: RefCell<Vec<String>>,
+}
+
+impl RunContext {
+    pub(crate) fn new(config: &ConformanceConfig) -> Self {
+        Self {
+            scenario: config.scenario.clone(),
+            nonce: RefCell::new(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|value| value.as_millis())
+                    .unwrap_or(0)
+                    .to_string(),
+            ),
+            current: Cell::new(""),
+            ids: RefCell::new(Vec::new()),
+            echoes: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn scenario(&self) -> &ScenarioConfig {
+        &self.scenario
+    }
+
+    /// `timeoutMs` schedule for waits expected to reach a terminal run state.
+    pub(crate) fn wait_schedule(&self) -> Vec<u64> {
+        self.scenario.wait.strategy.schedule()
+    }
+
+    pub(crate) fn wait_budget_ms(&self) -> u64 {
+        self.scenario.wait.strategy.budget_ms()
+    }
+
+    pub(crate) fn probe_timeout_ms(&self) -> u64 {
+        self.scenario.wait.probe_timeout_ms
+    }
+
+    /// Checks a completed run's `result.output` against `scenario.output`, or against the
+    /// reference gateway's `reference` echo when no matcher is configured.
+    pub(crate) fn check_output(&self, reference: &str, output: &Value) -> Result<(), String> {
+        match &self.scenario.output {
+            Some(matcher) => matcher.check(output),
+            None => Matcher::equals(reference).check(output),
+        }
+    }
+
+    /// Marks `scenario` as the owner of identifiers generated until the next call.
+    pub(crate) fn enter(&self, scenario: &'static str) {
+        self.current.set(scenario);
+    }
+
+    /// Identifier unique within the run, recorded against the current scenario.
+    pub(crate) fn unique_id(&self, prefix: &str) -> String {
+        let mut ids = self.ids.borrow_mut();
+        let id = format!("{prefix}-{}-{:04}", self.nonce.borrow(), ids.len() + 1);
+        ids.push((self.current.get(), id.clone()));
+        id
+    }
+
+    /// Run-specific part of every generated identifier.
+    pub(crate) fn nonce(&self) -> String {
+        self.nonce.borrow().clone()
+    }
+
+    /// Replaces the nonce with a higher-resolution one, so identifiers generated from here on
+    /// cannot collide with those of another run started in the same millisecond.
+    pub(crate) fn refresh_nonce(&self) {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        *self.nonce.borrow_mut() = format!("{nanos}p{}", process::id());
+    }
+
+    pub(crate) fn generated_ids(&self) -> usize {
+        self.ids.borrow().len()
+    }
+
+    /// Whether `payload` contains an identifier `scenario` generated.
+    pub(crate) fn carries_own_id(&self, scenario: &str, payload: &Value) -> bool {
+        let mut strings = Vec::new();
+        collect_strings(payload, &mut strings);
+        self.ids.borrow().iter().any(|(owner, id)| {
+            *owner == scenario && strings.iter().any(|text| text.contains(id.as_str()))
+        })
+    }
+
+    /// Keeps the [`Self::foreign_echoes`] of `payloads` for `run.identifier_isolation`.
+    pub(crate) fn record_echoes(&self, scenario: &str, payloads: &[Value]) {
+        let echoes = self.foreign_echoes(scenario, payloads);
+        self.echoes.borrow_mut().extend(echoes);
+    }
+
+    pub(crate) fn echoes(&self) -> Vec<String> {
+        self.echoes.borrow().clone()
+    }
+
+    /// Identifiers generated by other scenarios that appear in `payloads` received by `scenario`.
+    pub(crate) fn foreign_echoes(&self, scenario: &str, payloads: &[Value]) -> Vec<String> {
+        let mut strings = Vec::new();
+        payloads
+            .iter()
+            .for_each(|payload| collect_strings(payload, &mut strings));
+
+        let mut echoes = Vec::new();
+        for (owner, id) in self.ids.borrow().iter() {
+            if *owner == scenario || !strings.iter().any(|text| text.contains(id.as_str())) {
+                continue;
+            }
+            echoes.push(format!("{scenario} received {id} generated by {owner}"));
+        }
+        echoes
+    }
+}
+
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => strings.push(text),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields.iter().for_each(|(key, item)| {
+            strings.push(key);
+            collect_strings(item, strings);
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ConformanceConfig;
+
+    use super::RunContext;
+
+    #[test]
+    fn foreign_echoes_reports_identifiers_owned_by_other_scenarios() {
+        let ctx = RunContext::new(&ConformanceConfig::default());
+        ctx.enter("first");
+        let first = ctx.unique_id("conformance-first");
+        ctx.enter("second");
+        let second = ctx.unique_id("conformance-second");
+        assert_ne!(first, second);
+
+        let payloads = [json!({
+            "runIds": [format!("{second}-one")],
+            "result": { "sessionKey": format!("agent:main:{first}") }
+        })];
+        assert_eq!(
+            ctx.foreign_echoes("second", &payloads),
+            vec![format!("second received {first} generated by first")]
+        );
+        assert!(ctx.foreign_echoes("first", &payloads[..0]).is_empty());
+        assert_eq!(ctx.generated_ids(), 2);
+    }
+}
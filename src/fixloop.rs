@@ -0,0 +1,183 @@
+use serde_json::Value;
+
+use crate::{ConformanceOutcome, ConformanceRunner, ConformanceTransport};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixLoopTrigger {
+    Initial,
+    Keypress,
+    TargetVersionChanged {
+        previous: Option<String>,
+        current: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct FixLoopAttempt {
+    pub number: usize,
+    pub trigger: FixLoopTrigger,
+    pub outcome: ConformanceOutcome,
+    pub changes: Vec<String>,
+}
+
+impl FixLoopAttempt {
+    pub fn render(&self) -> String {
+        let trigger = match &self.trigger {
+            FixLoopTrigger::Initial => "initial".to_owned(),
+            FixLoopTrigger::Keypress => "keypress".to_owned(),
+            FixLoopTrigger::TargetVersionChanged { previous, current } => format!(
+                "target version {} -> {}",
+                previous.as_deref().unwrap_or("unknown"),
+                current.as_deref().unwrap_or("unknown")
+            ),
+        };
+
+        let mut lines = vec![format!(
+            "#{} [{}] {} ({trigger}) - {}",
+            self.number,
+            self.outcome.status(),
+            self.outcome.name,
+            self.outcome.detail
+        )];
+        lines.extend(self.changes.iter().map(|change| format!("    {change}")));
+        lines.join("\n")
+    }
+}
+
+pub struct FixLoop<T>
+where
+    T: ConformanceTransport,
+{
+    runner: ConformanceRunner<T>,
+    scenario: String,
+    attempts: usize,
+    previous: Option<ConformanceOutcome>,
+}
+
+impl<T> FixLoop<T>
+where
+    T: ConformanceTransport,
+{
+    pub fn new(runner: ConformanceRunner<T>, scenario: impl Into<String>) -> Result<Self, String> {
+        let scenario = scenario.into();
+        if !ConformanceRunner::<T>::scenario_names().contains(&scenario.as_str()) {
+            return Err(format!("unknown scenario `{scenario}`"));
+        }
+
+        Ok(Self {
+            runner,
+            scenario,
+            attempts: 0,
+            previous: None,
+        })
+    }
+
+    pub fn target_version(&self) -> Option<String> {
+        let info = self.runner.transport().get_json("/info").ok()?;
+        ["version", "build"]
+            .iter()
+            .find_map(|key| info.get(*key).and_then(Value::as_str))
+            .map(str::to_owned)
+    }
+
+    pub fn attempt(&mut self, trigger: FixLoopTrigger) -> FixLoopAttempt {
+        let outcome = self
+            .runner
+            .run_scenario(&self.scenario)
+            .expect("scenario name validated at construction");
+        self.attempts += 1;
+
+        let changes = match &self.previous {
+            Some(previous) => outcome_changes(previous, &outcome),
+            None => Vec::new(),
+        };
+        self.previous = Some(outcome.clone());
+
+        FixLoopAttempt {
+            number: self.attempts,
+            trigger,
+            outcome,
+            changes,
+        }
+    }
+}
+
+fn outcome_changes(previous: &ConformanceOutcome, current: &ConformanceOutcome) -> Vec<String> {
+    if previous.passed == current.passed && previous.detail == current.detail {
+        return vec!["(no change from previous attempt)".to_owned()];
+    }
+
+    let mut changes = Vec::new();
+    if previous.passed != current.passed {
+        changes.push(format!("passed: {} -> {}", previous.passed, current.passed));
+    }
+
+    let before = detail_fields(&previous.detail);
+    let after = detail_fields(&current.detail);
+    changes.extend(
+        before
+            .iter()
+            .filter(|field| !after.contains(field))
+            .map(|field| format!("- {field}")),
+    );
+    changes.extend(
+        after
+            .iter()
+            .filter(|field| !before.contains(field))
+            .map(|field| format!("+ {field}")),
+    );
+    changes
+}
+
+fn detail_fields(detail: &str) -> Vec<&str> {
+    let body = detail
+        .split_once(" found ")
+        .map_or(detail, |(_, found)| found);
+    body.split(", ")
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ConformanceOutcome;
+
+    use super::outcome_changes;
+
+    #[test]
+    fn outcome_changes_lists_changed_detail_fields() {
+        let previous = ConformanceOutcome::fail(
+            "ws.agent_wait_timeout_for_missing_run",
+            "expected timeout for unknown run, found status=None, runId=None",
+        );
+        let current = ConformanceOutcome::fail(
+            "ws.agent_wait_timeout_for_missing_run",
+            "expected timeout for unknown run, found status=Some(\"timeout\"), runId=None"
+                .to_owned(),
+        );
+
+        let changes = outcome_changes(&previous, &current);
+
+        assert_eq!(
+            changes,
+            vec![
+                "- status=None".to_owned(),
+                "+ status=Some(\"timeout\")".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn outcome_changes_reports_unchanged_attempts() {
+        let outcome =
+            ConformanceOutcome::pass("healthz.ok_true", "health endpoint returned ok=true");
+
+        let changes = outcome_changes(&outcome, &outcome);
+
+        assert_eq!(
+            changes,
+            vec!["(no change from previous attempt)".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use serde::Deserialize;
+
+/// Remediation hints for every scenario, bundled into the binary.
+const CATALOGUE: &str = include_str!("hints.toml");
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Hint {
+    implement: String,
+    #[serde(default)]
+    causes: Vec<String>,
+}
+
+fn catalogue() -> &'static BTreeMap<String, Hint> {
+    static HINTS: OnceLock<BTreeMap<String, Hint>> = OnceLock::new();
+    HINTS.get_or_init(|| toml::from_str(CATALOGUE).expect("bundled hint catalogue should parse"))
+}
+
+/// Notes telling an implementer what to build and what usually goes wrong for `scenario`.
+pub(crate) fn remediation_notes(scenario: &str) -> Vec<String> {
+    let Some(hint) = catalogue().get(scenario) else {
+        return Vec::new();
+    };
+    let mut notes = vec![format!("implement: {}", hint.implement)];
+    notes.extend(
+        hint.causes
+            .iter()
+            .map(|cause| format!("common cause: {cause}")),
+    );
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenario::Scenario;
+
+    use super::{catalogue, remediation_notes};
+
+    #[test]
+    fn catalogue_covers_exactly_the_known_scenarios() {
+        let names = Scenario::all()
+            .iter()
+            .map(Scenario::name)
+            .collect::<Vec<_>>();
+
+        for name in &names {
+            assert!(!remediation_notes(name).is_empty(), "{name} has no hint");
+        }
+        for name in catalogue().keys() {
+            assert!(names.contains(&name.as_str()), "{name} is not a scenario");
+        }
+        assert_eq!(
+            remediation_notes("healthz.ok_true")[0],
+            "implement: Serve GET /healthz with HTTP 200 and a JSON body containing \"ok\": true."
+        );
+    }
+}
@@ -1,22 +1,63 @@
+mod assessment;
+mod certify;
+mod config;
+mod context;
+mod fixloop;
+mod hints;
+mod matcher;
+mod matrix;
+mod recorder;
 mod report;
 mod runner;
 mod scenario;
+mod trace;
 mod transport;
 
-pub use report::{ConformanceOutcome, ConformanceReport};
+pub use assessment::{Reconciliation, SelfAssessment, SELF_ASSESSMENT_PATH};
+pub use certify::{target_metadata, CertificationPackage, CertifyError};
+pub use config::{
+    ChatSendConfig, ConfigError, ConformanceConfig, LimitsConfig, MessageExpectation,
+    RestartConfig, ScenarioConfig, TimingConfig, TraceConfig, WaitConfig, WaitStrategy,
+};
+pub use fixloop::{FixLoop, FixLoopAttempt, FixLoopTrigger};
+pub use matcher::Matcher;
+pub use matrix::{MatrixCell, MatrixColumn, MatrixReport, MatrixRow, MatrixTarget, MatrixTargets};
+pub use recorder::{ScenarioTranscript, TranscriptEntry};
+pub use report::{ConformanceOutcome, ConformanceReport, ScenarioTiming, Severity, SkipReason};
 pub use runner::ConformanceRunner;
-pub use transport::{ConformanceTransport, HttpTransport, TransportError};
+pub use scenario::ScenarioDescriptor;
+pub use transport::{
+    ConformanceTransport, HttpTransport, IpVersion, RawRequest, RawResponse, TransportError,
+};
 
 pub const EXPECTED_PROTOCOL_VERSION: u64 = 3;
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        net::IpAddr,
+        path::PathBuf,
+        rc::Rc,
+        thread,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
     use serde_json::{json, Value};
 
     use crate::{
-        ConformanceRunner, ConformanceTransport, TransportError, EXPECTED_PROTOCOL_VERSION,
+        ConformanceConfig, ConformanceRunner, ConformanceTransport, FixLoop, FixLoopTrigger,
+        RawRequest, RawResponse, Severity, SkipReason, TransportError, EXPECTED_PROTOCOL_VERSION,
+        SELF_ASSESSMENT_PATH,
     };
 
+    fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default()
+    }
+
     #[derive(Default)]
     struct MockTransport {
         healthz: Option<Value>,
@@ -26,6 +67,17 @@ mod tests {
         tools_invoke: Option<(u16, Value)>,
         tools_invoke_unknown: Option<(u16, Value)>,
         websocket_response: Option<Value>,
+        unreachable: Vec<IpAddr>,
+        clock_offset_ms: i64,
+        logged_out: RefCell<Vec<String>>,
+        /// Second-connection `channels.status` polls that still miss earlier logouts.
+        status_lag_polls: Cell<u32>,
+        /// `features` map sent in hello-ok.
+        features: Option<Value>,
+        self_assessment: Option<Value>,
+        /// `/info` reports `instanceId` `"boot-2"` once this file exists, so a restart hook
+        /// that creates it looks like a real restart.
+        instance_file: Option<PathBuf>,
     }
 
     impl ConformanceTransport for MockTransport {
@@ -39,10 +91,23 @@ mod tests {
                     .readyz
                     .clone()
                     .ok_or_else(|| TransportError::Protocol("missing readyz fixture".to_owned())),
-                "/info" => self
-                    .info
-                    .clone()
-                    .ok_or_else(|| TransportError::Protocol("missing info fixture".to_owned())),
+                "/info" => {
+                    let mut info = self.info.clone().ok_or_else(|| {
+                        TransportError::Protocol("missing info fixture".to_owned())
+                    })?;
+                    if let Some(path) = &self.instance_file {
+                        info["instanceId"] = json!(if path.exists() { "boot-2" } else { "boot-1" });
+                    }
+                    Ok(info)
+                }
+                SELF_ASSESSMENT_PATH => {
+                    self.self_assessment
+                        .clone()
+                        .ok_or_else(|| TransportError::Status {
+                            status: 404,
+                            path: path.to_owned(),
+                        })
+                }
                 _ => Err(TransportError::Protocol("unknown path".to_owned())),
             }
         }
@@ -104,6 +169,109 @@ mod tests {
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
+            if methods.as_slice() == ["connect"] {
+                let mut hello = json!({
+                    "ok": true,
+                    "payload": {
+                        "type": "hello-ok"
+                    }
+                });
+                if let Some(features) = &self.features {
+                    hello["payload"]["features"] = features.clone();
+                }
+                return Ok(vec![hello]);
+            }
+
+            if methods.len() > 1
+                && methods[1..]
+                    .iter()
+                    .all(|method| method.starts_with("voice."))
+            {
+                let mut responses = vec![json!({
+                    "ok": true,
+                    "payload": {
+                        "type": "hello-ok"
+                    }
+                })];
+                responses.extend(methods[1..].iter().map(|method| {
+                    json!({
+                        "ok": false,
+                        "error": {
+                            "code": "FEATURE_DISABLED",
+                            "message": format!("{method} is disabled")
+                        }
+                    })
+                }));
+                return Ok(responses);
+            }
+
+            if methods.len() > 1
+                && methods[1..]
+                    .iter()
+                    .all(|method| matches!(*method, "health" | "status"))
+            {
+                let mut responses = vec![json!({
+                    "ok": true,
+                    "payload": {
+                        "type": "hello-ok"
+                    }
+                })];
+                responses.extend(
+                    methods[1..]
+                        .iter()
+                        .map(|_| json!({ "ok": true, "payload": { "ok": true } })),
+                );
+                return Ok(responses);
+            }
+
+            if methods.as_slice() == ["connect", "agent.wait", "agent.wait"] {
+                let mut responses = vec![json!({
+                    "ok": true,
+                    "payload": {
+                        "type": "hello-ok"
+                    }
+                })];
+                for frame in &frames[1..] {
+                    if frame["params"]["runId"].is_string() {
+                        return Err(TransportError::Protocol(
+                            "unexpected valid wait runId in websocket fixture".to_owned(),
+                        ));
+                    }
+                    responses.push(json!({
+                        "ok": false,
+                        "error": {
+                            "code": "INVALID_REQUEST",
+                            "message": "invalid agent.wait params: runId must be a string"
+                        }
+                    }));
+                }
+                return Ok(responses);
+            }
+
+            if methods.as_slice() == ["connect", "chat.send"] {
+                let message = &frames[1]["params"]["message"];
+                if message.as_str().is_some_and(|message| !message.is_empty()) {
+                    return Err(TransportError::Protocol(
+                        "unexpected valid chat.send message in websocket fixture".to_owned(),
+                    ));
+                }
+                return Ok(vec![
+                    json!({
+                        "ok": true,
+                        "payload": {
+                            "type": "hello-ok"
+                        }
+                    }),
+                    json!({
+                        "ok": false,
+                        "error": {
+                            "code": "INVALID_REQUEST",
+                            "message": "invalid chat.send params: message must be a non-empty string"
+                        }
+                    }),
+                ]);
+            }
+
             if methods.as_slice() == ["connect", "agent.wait"] {
                 let wait_run_id = frames[1]
                     .get("params")
@@ -114,6 +282,10 @@ mod tests {
                             "missing wait runId in websocket fixture".to_owned(),
                         )
                     })?;
+                if wait_run_id.starts_with("conformance-missing") {
+                    let timeout_ms = frames[1]["params"]["timeoutMs"].as_u64().unwrap_or(0);
+                    thread::sleep(Duration::from_millis(timeout_ms));
+                }
 
                 return Ok(vec![
                     json!({
@@ -133,6 +305,27 @@ mod tests {
             }
 
             if methods.as_slice() == ["connect", "channels.status"] {
+                let mut accounts = vec![json!({
+                    "accountId": "default",
+                    "connected": true,
+                    "kind": "internal",
+                    "loggedOutAtMs": Value::Null
+                })];
+                let lag = self.status_lag_polls.get();
+                self.status_lag_polls.set(lag.saturating_sub(1));
+                let logged_out = if lag == 0 {
+                    self.logged_out.borrow().clone()
+                } else {
+                    Vec::new()
+                };
+                accounts.extend(logged_out.iter().map(|account_id| {
+                    json!({
+                        "accountId": account_id,
+                        "connected": false,
+                        "kind": "internal",
+                        "loggedOutAtMs": now_ms()
+                    })
+                }));
                 return Ok(vec![
                     json!({
                         "ok": true,
@@ -164,12 +357,7 @@ mod tests {
                                 }
                             },
                             "channelAccounts": {
-                                "webchat": [{
-                                    "accountId": "default",
-                                    "connected": true,
-                                    "kind": "internal",
-                                    "loggedOutAtMs": Value::Null
-                                }]
+                                "webchat": accounts
                             },
                             "channelDefaultAccountId": {
                                 "webchat": "default"
@@ -180,6 +368,17 @@ mod tests {
             }
 
             if methods.as_slice() == ["connect", "channels.logout", "channels.status"] {
+                let account_id = frames[1]
+                    .get("params")
+                    .and_then(|params| params.get("accountId"))
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| {
+                        TransportError::Protocol(
+                            "missing logout accountId in websocket fixture".to_owned(),
+                        )
+                    })?
+                    .to_owned();
+                self.logged_out.borrow_mut().push(account_id.clone());
                 return Ok(vec![
                     json!({
                         "ok": true,
@@ -192,7 +391,7 @@ mod tests {
                         "payload": {
                             "ok": true,
                             "channel": "webchat",
-                            "accountId": "ops",
+                            "accountId": account_id,
                             "loggedOut": true
                         }
                     }),
@@ -208,10 +407,10 @@ mod tests {
                                 },
                                 {
                                     "id": "webchat",
-                                    "accountId": "ops",
+                                    "accountId": account_id,
                                     "connected": false,
                                     "kind": "internal",
-                                    "loggedOutAtMs": 42
+                                    "loggedOutAtMs": now_ms()
                                 }
                             ],
                             "channelOrder": ["webchat"],
@@ -237,10 +436,10 @@ mod tests {
                                         "loggedOutAtMs": Value::Null
                                     },
                                     {
-                                        "accountId": "ops",
+                                        "accountId": account_id,
                                         "connected": false,
                                         "kind": "internal",
-                                        "loggedOutAtMs": 42
+                                        "loggedOutAtMs": now_ms()
                                     }
                                 ]
                             },
@@ -729,10 +928,262 @@ mod tests {
                 "unsupported websocket fixture methods: {methods:?}"
             )))
         }
+
+        fn request_raw(&self, request: &RawRequest) -> Result<RawResponse, TransportError> {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/tools/invoke") => {
+                    let content_type = request.header_value("content-type");
+                    let (status, body) = match content_type {
+                        Some(value) if value.starts_with("application/json") => {
+                            self.tools_invoke.clone().ok_or_else(|| {
+                                TransportError::Protocol("missing tools invoke fixture".to_owned())
+                            })?
+                        }
+                        Some(_) => (
+                            415,
+                            json!({ "ok": false, "error": { "type": "unsupported_media_type" } }),
+                        ),
+                        None => (
+                            400,
+                            json!({ "ok": false, "error": { "type": "invalid_request" } }),
+                        ),
+                    };
+                    Ok(RawResponse {
+                        status,
+                        headers: vec![("content-type".to_owned(), "application/json".to_owned())],
+                        body: body.to_string().into_bytes(),
+                    })
+                }
+                ("GET", "/healthz") => {
+                    let payload = self.healthz.clone().ok_or_else(|| {
+                        TransportError::Protocol("missing healthz fixture".to_owned())
+                    })?;
+                    let offset = Duration::from_millis(self.clock_offset_ms.unsigned_abs());
+                    let date = if self.clock_offset_ms < 0 {
+                        SystemTime::now() - offset
+                    } else {
+                        SystemTime::now() + offset
+                    };
+                    Ok(RawResponse {
+                        status: 200,
+                        headers: vec![
+                            ("content-type".to_owned(), "application/json".to_owned()),
+                            ("date".to_owned(), httpdate::fmt_http_date(date)),
+                        ],
+                        body: payload.to_string().into_bytes(),
+                    })
+                }
+                ("HEAD", "/healthz") => {
+                    self.healthz.as_ref().ok_or_else(|| {
+                        TransportError::Protocol("missing healthz fixture".to_owned())
+                    })?;
+                    Ok(RawResponse {
+                        status: 200,
+                        headers: vec![("content-type".to_owned(), "application/json".to_owned())],
+                        body: Vec::new(),
+                    })
+                }
+                _ => Err(TransportError::Protocol(format!(
+                    "unsupported raw fixture request: {} {}",
+                    request.method, request.path
+                ))),
+            }
+        }
+
+        fn resolve_target(&self) -> Result<Vec<IpAddr>, TransportError> {
+            Ok(vec![
+                "127.0.0.1".parse().expect("IPv4 literal"),
+                "::1".parse().expect("IPv6 literal"),
+            ])
+        }
+
+        fn request_raw_via(
+            &self,
+            address: IpAddr,
+            request: &RawRequest,
+        ) -> Result<RawResponse, TransportError> {
+            if self.unreachable.contains(&address) {
+                return Err(TransportError::Http(format!(
+                    "connect to {address} refused"
+                )));
+            }
+            self.request_raw(request)
+        }
+    }
+
+    fn passing_transport() -> MockTransport {
+        MockTransport {
+            healthz: Some(json!({ "ok": true })),
+            readyz: Some(json!({ "ok": true })),
+            info: Some(json!({
+                "protocolVersion": EXPECTED_PROTOCOL_VERSION,
+                "methods": ["health", "status"]
+            })),
+            unknown_webhook: Some((
+                404,
+                json!({
+                    "ok": false,
+                    "error": {
+                        "code": "NOT_FOUND"
+                    }
+                }),
+            )),
+            tools_invoke: Some((
+                200,
+                json!({
+                    "ok": true,
+                    "result": {
+                        "ok": true
+                    }
+                }),
+            )),
+            tools_invoke_unknown: Some((
+                404,
+                json!({
+                    "ok": false,
+                    "error": {
+                        "type": "not_found"
+                    }
+                }),
+            )),
+            websocket_response: Some(json!({
+                "type": "res",
+                "id": "conformance-handshake-invalid-1",
+                "ok": false,
+                "error": {
+                    "code": "INVALID_REQUEST"
+                }
+            })),
+            unreachable: Vec::new(),
+            clock_offset_ms: 0,
+            logged_out: RefCell::default(),
+            status_lag_polls: Cell::default(),
+            features: Some(json!({
+                "core": { "enabled": true, "methods": ["health", "status"] },
+                "voice": { "enabled": false, "methods": ["voice.start", "voice.stop"] }
+            })),
+            self_assessment: None,
+            instance_file: None,
+        }
+    }
+
+    type GetJson = dyn Fn(&MockTransport, &str) -> Result<Value, TransportError>;
+    type Exchange = dyn Fn(&MockTransport, &[Value]) -> Result<Vec<Value>, TransportError>;
+    type RequestRaw = dyn Fn(&MockTransport, &RawRequest) -> Result<RawResponse, TransportError>;
+
+    /// [`MockTransport`] with some calls replaced; each override receives the inner mock so it
+    /// can adjust the mock's answer instead of writing its own.
+    struct Tweaked {
+        inner: MockTransport,
+        get_json: Option<Box<GetJson>>,
+        websocket_exchange: Option<Box<Exchange>>,
+        request_raw: Option<Box<RequestRaw>>,
+        connection_setup_time: Option<Box<dyn Fn() -> Duration>>,
+    }
+
+    impl Tweaked {
+        fn new(inner: MockTransport) -> Self {
+            Self {
+                inner,
+                get_json: None,
+                websocket_exchange: None,
+                request_raw: None,
+                connection_setup_time: None,
+            }
+        }
+
+        fn with_get_json(
+            mut self,
+            get_json: impl Fn(&MockTransport, &str) -> Result<Value, TransportError> + 'static,
+        ) -> Self {
+            self.get_json = Some(Box::new(get_json));
+            self
+        }
+
+        fn with_websocket_exchange(
+            mut self,
+            exchange: impl Fn(&MockTransport, &[Value]) -> Result<Vec<Value>, TransportError> + 'static,
+        ) -> Self {
+            self.websocket_exchange = Some(Box::new(exchange));
+            self
+        }
+
+        fn with_request_raw(
+            mut self,
+            request_raw: impl Fn(&MockTransport, &RawRequest) -> Result<RawResponse, TransportError>
+                + 'static,
+        ) -> Self {
+            self.request_raw = Some(Box::new(request_raw));
+            self
+        }
+
+        fn with_connection_setup_time(mut self, setup: impl Fn() -> Duration + 'static) -> Self {
+            self.connection_setup_time = Some(Box::new(setup));
+            self
+        }
+    }
+
+    impl ConformanceTransport for Tweaked {
+        fn get_json(&self, path: &str) -> Result<Value, TransportError> {
+            match &self.get_json {
+                Some(get_json) => get_json(&self.inner, path),
+                None => self.inner.get_json(path),
+            }
+        }
+
+        fn post_json(&self, path: &str, body: &Value) -> Result<(u16, Value), TransportError> {
+            self.inner.post_json(path, body)
+        }
+
+        fn websocket_first_response(&self, frame: &Value) -> Result<Value, TransportError> {
+            self.inner.websocket_first_response(frame)
+        }
+
+        fn websocket_exchange(&self, frames: &[Value]) -> Result<Vec<Value>, TransportError> {
+            match &self.websocket_exchange {
+                Some(exchange) => exchange(&self.inner, frames),
+                None => self.inner.websocket_exchange(frames),
+            }
+        }
+
+        fn request_raw(&self, request: &RawRequest) -> Result<RawResponse, TransportError> {
+            match &self.request_raw {
+                Some(request_raw) => request_raw(&self.inner, request),
+                None => self.inner.request_raw(request),
+            }
+        }
+
+        fn resolve_target(&self) -> Result<Vec<IpAddr>, TransportError> {
+            self.inner.resolve_target()
+        }
+
+        fn request_raw_via(
+            &self,
+            address: IpAddr,
+            request: &RawRequest,
+        ) -> Result<RawResponse, TransportError> {
+            self.inner.request_raw_via(address, request)
+        }
+
+        fn connection_setup_time(&self) -> Duration {
+            match &self.connection_setup_time {
+                Some(setup) => setup(),
+                None => self.inner.connection_setup_time(),
+            }
+        }
+    }
+
+    /// Fresh marker path for [`MockTransport::instance_file`], removed if a previous run left it.
+    fn instance_file(label: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("reclaw-conformance-{label}-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
     }
 
     #[test]
     fn runner_reports_all_pass_when_invariants_hold() {
+        let marker = instance_file("all-pass");
         let transport = MockTransport {
             healthz: Some(json!({ "ok": true })),
             readyz: Some(json!({ "ok": true })),
@@ -775,13 +1226,39 @@ mod tests {
                     "code": "INVALID_REQUEST"
                 }
             })),
+            unreachable: Vec::new(),
+            clock_offset_ms: 0,
+            logged_out: RefCell::default(),
+            status_lag_polls: Cell::default(),
+            features: Some(json!({
+                "core": { "enabled": true, "methods": ["health", "status"] },
+                "voice": { "enabled": false, "methods": ["voice.start", "voice.stop"] }
+            })),
+            self_assessment: None,
+            instance_file: Some(marker.clone()),
         };
 
-        let report = ConformanceRunner::new(transport).run();
+        let config = ConformanceConfig::from_toml(&format!(
+            r#"
+            profiles = ["v2-compat"]
+
+            [scenario]
+            dual_stack = true
 
-        assert_eq!(report.total, 21);
+            [scenario.restart]
+            hook = ["touch", "{}"]
+            poll_ms = 5
+            runs_persist = false
+            "#,
+            marker.display()
+        ))
+        .expect("config should parse");
+        let report = ConformanceRunner::new(transport).with_config(config).run();
+
+        assert_eq!(report.total, 38);
         assert_eq!(report.failed, 0);
         assert!(report.outcomes.iter().all(|outcome| outcome.passed));
+        let _ = std::fs::remove_file(&marker);
     }
 
     #[test]
@@ -828,11 +1305,18 @@ mod tests {
                     "code": "INVALID_REQUEST"
                 }
             })),
+            unreachable: Vec::new(),
+            clock_offset_ms: 0,
+            logged_out: RefCell::default(),
+            status_lag_polls: Cell::default(),
+            features: None,
+            self_assessment: None,
+            instance_file: None,
         };
 
         let report = ConformanceRunner::new(transport).run();
 
-        assert_eq!(report.total, 21);
+        assert_eq!(report.total, 38);
         assert_eq!(report.failed, 1);
         let protocol_case = report
             .outcomes
@@ -840,5 +1324,1032 @@ mod tests {
             .find(|entry| entry.name == "info.protocol_version")
             .expect("protocol scenario should exist");
         assert!(!protocol_case.passed);
+        assert!(protocol_case.notes[0].starts_with("implement: Serve GET /info"));
+        assert!(protocol_case
+            .notes
+            .iter()
+            .skip(1)
+            .all(|note| note.starts_with("common cause: ")));
+        assert!(report.to_markdown().contains(
+            "## Remediation\n\n### `info.protocol_version`\n\n- implement: Serve GET /info"
+        ));
+        assert!(report
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.passed)
+            .all(|outcome| outcome.notes.is_empty()));
+    }
+
+    #[test]
+    fn runner_outcome_names_match_scenario_names() {
+        let runner = ConformanceRunner::new(passing_transport());
+
+        for name in ConformanceRunner::<MockTransport>::scenario_names() {
+            let outcome = runner
+                .run_scenario(name)
+                .expect("listed scenario should run");
+            assert_eq!(outcome.name, name);
+        }
+        assert!(runner.run_scenario("unknown.scenario").is_none());
+    }
+
+    #[test]
+    fn fixloop_reruns_single_scenario_and_reports_target_version() {
+        let mut transport = passing_transport();
+        transport.info = Some(json!({
+            "protocolVersion": EXPECTED_PROTOCOL_VERSION,
+            "methods": ["health", "status"],
+            "version": "1.2.3"
+        }));
+        let mut fixloop = FixLoop::new(ConformanceRunner::new(transport), "healthz.ok_true")
+            .expect("known scenario should be accepted");
+
+        let first = fixloop.attempt(FixLoopTrigger::Initial);
+        let second = fixloop.attempt(FixLoopTrigger::Keypress);
+
+        assert_eq!(fixloop.target_version().as_deref(), Some("1.2.3"));
+        assert!(first.outcome.passed);
+        assert!(first.changes.is_empty());
+        assert_eq!(second.number, 2);
+        assert_eq!(
+            second.changes,
+            vec!["(no change from previous attempt)".to_owned()]
+        );
+        assert!(FixLoop::new(
+            ConformanceRunner::new(passing_transport()),
+            "unknown.scenario"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn runner_annotates_failing_outcomes_with_tag_owner() {
+        let mut transport = passing_transport();
+        transport.healthz = Some(json!({ "ok": false }));
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [owners]
+            health = "platform-sre"
+            http = "edge"
+            "#,
+        )
+        .expect("config should parse");
+
+        let report = ConformanceRunner::new(transport).with_config(config).run();
+
+        let healthz = report
+            .outcomes
+            .iter()
+            .find(|entry| entry.name == "healthz.ok_true")
+            .expect("healthz scenario should exist");
+        assert_eq!(healthz.owner.as_deref(), Some("platform-sre"));
+        assert!(report
+            .outcomes
+            .iter()
+            .filter(|entry| entry.passed)
+            .all(|entry| entry.owner.is_none()));
+        let dual_stack = report
+            .outcomes
+            .iter()
+            .find(|entry| entry.name == "healthz.dual_stack_reachable")
+            .expect("dual-stack scenario should exist");
+        assert_eq!(dual_stack.skipped, Some(SkipReason::NotConfigured));
+        assert!(dual_stack.owner.is_none());
+        assert!(dual_stack.notes.is_empty());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| FAIL | `healthz.ok_true` | required | platform-sre |"));
+        assert!(markdown.contains("### platform-sre\n\n- `healthz.ok_true`"));
+    }
+
+    #[test]
+    fn runner_captures_transcripts_only_when_enabled() {
+        let report = ConformanceRunner::new(passing_transport()).run();
+        assert!(report.transcripts.is_empty());
+
+        let report = ConformanceRunner::new(passing_transport())
+            .with_transcripts(true)
+            .run();
+
+        assert_eq!(
+            report.transcripts.len(),
+            ConformanceRunner::<MockTransport>::scenario_names().len()
+        );
+        let healthz = &report.transcripts[0];
+        assert_eq!(healthz.scenario, "healthz.ok_true");
+        assert_eq!(healthz.entries[0].path, "/healthz");
+        assert_eq!(healthz.entries[0].response, Some(json!({ "ok": true })));
+
+        let report = ConformanceRunner::new(passing_transport())
+            .with_transcripts(true)
+            .with_max_capture_bytes(15)
+            .with_selection(vec!["healthz.ok_true".to_owned()])
+            .run();
+        assert!(report.is_passing());
+        let response = report.transcripts[0].entries[0]
+            .response
+            .as_ref()
+            .expect("response should be recorded");
+        assert_eq!(response["truncated"], true);
+        assert_eq!(response["originalBytes"], 11);
+    }
+
+    /// Reports `timeout` for the first two `agent.wait` polls of a run, recording every
+    /// requested `timeoutMs`; re-polls fail outright when `repoll_error` is set.
+    fn slow_run_transport(wait_timeouts: Rc<RefCell<Vec<u64>>>, repoll_error: bool) -> Tweaked {
+        Tweaked::new(passing_transport()).with_websocket_exchange(move |inner, frames| {
+            let last = frames.last().expect("exchange should include frames");
+            if last["method"] == "agent.wait" {
+                wait_timeouts.borrow_mut().push(
+                    last["params"]["timeoutMs"]
+                        .as_u64()
+                        .expect("wait timeout should be numeric"),
+                );
+            }
+
+            if repoll_error && frames.len() == 2 && last["method"] == "agent.wait" {
+                return Err(TransportError::Protocol("connection reset".to_owned()));
+            }
+            let mut responses = inner.websocket_exchange(frames)?;
+            if frames.len() == 2 && last["method"] == "agent.wait" {
+                responses[1] = if wait_timeouts.borrow().len() < 3 {
+                    json!({ "ok": true, "payload": { "status": "timeout" } })
+                } else {
+                    json!({
+                        "ok": true,
+                        "payload": {
+                            "status": "completed",
+                            "result": {
+                                "output": "Echo: conformance deferred",
+                                "sessionKey": format!("agent:main:{}", last["params"]["runId"].as_str().unwrap_or_default())
+                            }
+                        }
+                    })
+                };
+            } else if frames.len() == 3 && last["method"] == "agent.wait" {
+                responses[2] = json!({ "ok": true, "payload": { "status": "timeout" } });
+            }
+            Ok(responses)
+        })
+    }
+
+    #[test]
+    fn runner_repolls_wait_following_configured_strategy() {
+        let wait_timeouts = Rc::new(RefCell::new(Vec::new()));
+        let transport = slow_run_transport(Rc::clone(&wait_timeouts), false);
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.wait.strategy]
+            kind = "exponential"
+            initial_ms = 250
+            factor = 2
+            max_ms = 4000
+            attempts = 4
+            "#,
+        )
+        .expect("config should parse");
+        let runner = ConformanceRunner::new(transport).with_config(config);
+
+        let outcome = runner
+            .run_scenario("ws.agent_deferred_wait_completes")
+            .expect("scenario should exist");
+
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert_eq!(*wait_timeouts.borrow(), vec![250, 500, 1000]);
+    }
+
+    #[test]
+    fn runner_reports_wait_repoll_transport_errors() {
+        let transport = slow_run_transport(Rc::new(RefCell::new(Vec::new())), true);
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.wait.strategy]
+            kind = "fixed"
+            timeout_ms = 100
+            attempts = 3
+            "#,
+        )
+        .expect("config should parse");
+
+        let outcome = ConformanceRunner::new(transport)
+            .with_config(config)
+            .run_scenario("ws.agent_deferred_wait_completes")
+            .expect("scenario should exist");
+
+        assert!(!outcome.passed);
+        assert!(
+            outcome.detail.contains("re-poll 1") && outcome.detail.contains("connection reset"),
+            "{}",
+            outcome.detail
+        );
+    }
+
+    #[test]
+    fn runner_uses_configured_protocol_expectations() {
+        let mut transport = passing_transport();
+        transport.info = Some(json!({
+            "protocolVersion": 4,
+            "methods": ["health", "status"]
+        }));
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            expected_protocol_version = 4
+            max_protocol = 4
+            "#,
+        )
+        .expect("config should parse");
+
+        let outcome = ConformanceRunner::new(transport)
+            .with_config(config)
+            .run_scenario("info.protocol_version")
+            .expect("scenario should exist");
+
+        assert!(outcome.passed, "{}", outcome.detail);
+    }
+
+    #[test]
+    fn runner_uses_configured_chat_send_message_expectations() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.chat_send]
+            empty = "accept"
+            "#,
+        )
+        .expect("config should parse");
+        let runner = ConformanceRunner::new(passing_transport()).with_config(config);
+
+        let empty = runner
+            .run_scenario("ws.chat_send_rejects_empty_message")
+            .expect("scenario should exist");
+        let null = runner
+            .run_scenario("ws.chat_send_rejects_null_message")
+            .expect("scenario should exist");
+
+        assert!(!empty.passed);
+        assert!(
+            empty.detail.contains("expected chat.send to accept"),
+            "{}",
+            empty.detail
+        );
+        assert!(null.passed, "{}", null.detail);
+    }
+
+    #[test]
+    fn runner_accounts_payload_bytes_and_warns_on_oversized_responses() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [limits]
+            max_response_bytes = 400
+            "#,
+        )
+        .expect("config should parse");
+
+        let report = ConformanceRunner::new(passing_transport())
+            .with_config(config)
+            .run();
+
+        let healthz = &report.outcomes[0];
+        assert_eq!(healthz.bytes_sent, 0);
+        assert_eq!(healthz.bytes_received, r#"{"ok":true}"#.len() as u64);
+        assert_eq!(
+            report.bytes_received,
+            report
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.bytes_received)
+                .sum::<u64>()
+        );
+        assert!(report.bytes_sent > 0);
+        assert!(!report.warnings.is_empty());
+        assert!(report
+            .warnings
+            .iter()
+            .all(|warning| warning.starts_with("ws.channels_")
+                && warning.contains("WS channels.status response was")));
+    }
+
+    #[test]
+    fn runner_reports_recommended_failures_without_failing_run() {
+        let transport = Tweaked::new(passing_transport()).with_request_raw(|inner, request| {
+            let mut response = inner.request_raw(request)?;
+            if request.method == "HEAD" {
+                response.body = br#"{"ok":true}"#.to_vec();
+            }
+            Ok(response)
+        });
+        let report = ConformanceRunner::new(transport).run();
+        let outcome = report
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.name == "healthz.head_no_body")
+            .expect("head scenario should run");
+
+        assert!(!outcome.passed);
+        assert_eq!(outcome.severity, Severity::Recommended);
+        assert!(outcome.detail.contains("body bytes=11"));
+        assert_eq!(report.failed, 1);
+        assert!(report.is_passing());
+    }
+
+    #[test]
+    fn runner_skips_quarantined_and_read_only_scenarios_with_reasons() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            quarantine = ["healthz.ok_true"]
+            "#,
+        )
+        .expect("config should parse");
+
+        let report = ConformanceRunner::new(passing_transport())
+            .with_config(config)
+            .with_read_only(true)
+            .run();
+
+        let reason_of = |name: &str| {
+            report
+                .outcomes
+                .iter()
+                .find(|outcome| outcome.name == name)
+                .and_then(|outcome| outcome.skipped)
+        };
+        assert_eq!(reason_of("healthz.ok_true"), Some(SkipReason::Quarantined));
+        assert_eq!(
+            reason_of("ws.channels_logout_account_persists"),
+            Some(SkipReason::ReadOnly)
+        );
+        assert_eq!(reason_of("ws.channels_status_includes_account_views"), None);
+        assert_eq!(report.skipped, 21);
+        assert_eq!(report.failed, 0);
+        assert!(report.is_passing());
+
+        let json = serde_json::to_value(&report).expect("report should serialize");
+        assert_eq!(json["outcomes"][0]["skipped"], "quarantined");
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| SKIP (read_only) | `ws.channels_logout_account_persists` |"));
+        assert!(markdown.contains("### quarantined\n\n- `healthz.ok_true`"));
+    }
+
+    #[test]
+    fn runner_reports_asymmetric_dual_stack_reachability() {
+        let report = ConformanceRunner::new(passing_transport()).run();
+        let outcome = report
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.name == "healthz.dual_stack_reachable")
+            .expect("dual-stack scenario should run");
+        assert_eq!(outcome.skipped, Some(SkipReason::NotConfigured));
+
+        let mut transport = passing_transport();
+        transport.unreachable = vec!["::1".parse().expect("IPv6 literal")];
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            dual_stack = true
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = ConformanceRunner::new(transport)
+            .with_config(config)
+            .run_scenario("healthz.dual_stack_reachable")
+            .expect("dual-stack scenario should exist");
+
+        assert!(!outcome.passed);
+        assert!(outcome.skipped.is_none());
+        assert!(outcome
+            .detail
+            .starts_with("asymmetric reachability: health endpoint failed via ::1"));
+    }
+
+    #[test]
+    fn runner_flags_gateway_clock_skew_beyond_configured_limit() {
+        let mut transport = passing_transport();
+        transport.clock_offset_ms = -90_000;
+        let runner = ConformanceRunner::new(transport);
+
+        let outcome = runner
+            .run_scenario("healthz.date_within_clock_skew")
+            .expect("clock scenario should exist");
+        assert!(!outcome.passed);
+        assert_eq!(outcome.severity, Severity::Recommended);
+        assert!(outcome.detail.contains("behind the runner clock"));
+
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            max_clock_skew_ms = 120000
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = runner
+            .with_config(config)
+            .run_scenario("healthz.date_within_clock_skew")
+            .expect("clock scenario should exist");
+        assert!(outcome.passed, "{}", outcome.detail);
+    }
+
+    #[test]
+    fn runner_measures_channel_logout_propagation_across_connections() {
+        let transport = passing_transport();
+        transport.status_lag_polls.set(2);
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            propagation_poll_ms = 5
+            "#,
+        )
+        .expect("config should parse");
+        let runner = ConformanceRunner::new(transport).with_config(config);
+        let outcome = runner
+            .run_scenario("ws.channels_logout_read_your_writes")
+            .expect("read-your-writes scenario should exist");
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert!(outcome.detail.contains("(3 polls, tolerance 1000ms)"));
+
+        runner.transport().status_lag_polls.set(2);
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario]
+            propagation_tolerance_ms = 0
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = runner
+            .with_config(config)
+            .run_scenario("ws.channels_logout_read_your_writes")
+            .expect("read-your-writes scenario should exist");
+        assert!(!outcome.passed);
+        assert_eq!(
+            outcome.detail,
+            "logout visible on the same connection but not on a second connection within 0ms (1 polls)"
+        );
+
+        let stale = Tweaked::new(passing_transport()).with_websocket_exchange(|inner, frames| {
+            let mut responses = inner.websocket_exchange(frames)?;
+            for response in &mut responses {
+                if let Some(accounts) = response
+                    .pointer_mut("/payload/channelAccounts/webchat")
+                    .and_then(Value::as_array_mut)
+                {
+                    for account in accounts {
+                        account["loggedOutAtMs"] = json!(42);
+                    }
+                }
+            }
+            Ok(responses)
+        });
+        let outcome = ConformanceRunner::new(stale)
+            .run_scenario("ws.channels_logout_read_your_writes")
+            .expect("read-your-writes scenario should exist");
+        assert!(!outcome.passed);
+        assert!(
+            outcome.detail.contains("loggedOutAtMs=42 is ")
+                && outcome.detail.ends_with("beyond 5000ms"),
+            "{}",
+            outcome.detail
+        );
+    }
+
+    #[test]
+    fn runner_checks_run_output_with_configured_matcher() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.output]
+            kind = "regex"
+            pattern = "^Echo: conformance"
+            "#,
+        )
+        .expect("config should parse");
+        let runner = ConformanceRunner::new(passing_transport()).with_config(config);
+        for name in [
+            "ws.agent_deferred_wait_completes",
+            "ws.chat_send_deferred_wait_completes",
+        ] {
+            let outcome = runner.run_scenario(name).expect("scenario should exist");
+            assert!(outcome.passed, "{name}: {}", outcome.detail);
+        }
+
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.output]
+            kind = "one_of"
+            values = ["Hello!", "Hi there."]
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = runner
+            .with_config(config)
+            .run_scenario("ws.agent_deferred_wait_completes")
+            .expect("scenario should exist");
+        assert!(!outcome.passed);
+        assert!(outcome.detail.contains(
+            r#"outputMismatch=Some("\"Echo: conformance deferred\" is not one of [\"Hello!\",\"Hi there.\"]")"#
+        ));
+    }
+
+    #[test]
+    fn runner_gates_v2_compat_scenarios_on_profile() {
+        let transport =
+            Tweaked::new(passing_transport()).with_websocket_exchange(|inner, frames| {
+                let mut responses = inner.websocket_exchange(frames)?;
+                for response in &mut responses {
+                    if let Some(payload) =
+                        response.get_mut("payload").and_then(Value::as_object_mut)
+                    {
+                        payload.remove("channels");
+                    }
+                }
+                Ok(responses)
+            });
+        let runner = ConformanceRunner::new(transport);
+        let outcome = runner
+            .run_scenario("compat.channels_status_legacy_channels_array")
+            .expect("compat scenario should exist");
+        assert_eq!(outcome.skipped, Some(SkipReason::NotConfigured));
+        assert!(outcome.detail.contains("\"v2-compat\" profile"));
+
+        let config = ConformanceConfig::from_toml(r#"profiles = ["v2-compat"]"#)
+            .expect("config should parse");
+        let runner = runner.with_config(config);
+        let outcome = runner
+            .run_scenario("compat.channels_status_legacy_channels_array")
+            .expect("compat scenario should exist");
+        assert!(outcome.is_failure());
+        assert_eq!(
+            outcome.detail,
+            "channels.status is missing the legacy v2 channels array"
+        );
+        let outcome = runner
+            .run_scenario("compat.channels_status_legacy_channel_labels")
+            .expect("compat scenario should exist");
+        assert!(outcome.passed, "{}", outcome.detail);
+    }
+
+    #[test]
+    fn runner_retries_identifier_collisions_with_fresh_identifiers() {
+        let run_ids = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&run_ids);
+        let runner = ConformanceRunner::new(
+            Tweaked::new(passing_transport()).with_websocket_exchange(move |inner, frames| {
+                let mut responses = inner.websocket_exchange(frames)?;
+                if frames[1]["method"] == "agent" {
+                    let mut run_ids = seen.borrow_mut();
+                    run_ids.push(frames[1]["params"]["runId"].to_string());
+                    if run_ids.len() == 1 {
+                        responses[1] = json!({
+                            "ok": false,
+                            "error": { "code": "DUPLICATE", "message": "runId already exists" }
+                        });
+                    }
+                }
+                Ok(responses)
+            }),
+        );
+        let outcome = runner
+            .run_scenario("ws.agent_deferred_wait_completes")
+            .expect("agent scenario should exist");
+
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert_eq!(outcome.retried_after.as_deref(), Some("DUPLICATE"));
+        assert!(outcome
+            .detail
+            .ends_with("(retried with fresh identifiers after DUPLICATE)"));
+        let run_ids = run_ids.borrow();
+        assert_eq!(run_ids.len(), 2);
+        assert_ne!(run_ids[0], run_ids[1]);
+
+        let outcome = runner
+            .run_scenario("ws.chat_abort_unknown_run_noop")
+            .expect("abort scenario should exist");
+        assert!(outcome.retried_after.is_none());
+
+        let transport =
+            Tweaked::new(passing_transport()).with_websocket_exchange(|inner, frames| {
+                let mut responses = inner.websocket_exchange(frames)?;
+                for (frame, response) in frames.iter().zip(responses.iter_mut()).skip(1) {
+                    if frame["method"] == "voice.start" {
+                        *response = json!({ "ok": false, "error": { "code": "DUPLICATE" } });
+                    }
+                }
+                Ok(responses)
+            });
+        let outcome = ConformanceRunner::new(transport)
+            .run_scenario("ws.feature_disabled_methods_rejected")
+            .expect("feature scenario should exist");
+        assert!(outcome.is_failure());
+        assert!(
+            outcome.retried_after.is_none(),
+            "DUPLICATE for a request without generated identifiers is not a collision"
+        );
+    }
+
+    #[test]
+    fn feature_scenarios_follow_hello_features_map() {
+        let mut transport = passing_transport();
+        transport.features = Some(json!({
+            "voice": { "enabled": true, "methods": ["voice.start"] },
+            "canvas": { "enabled": false, "methods": ["chat.send"] }
+        }));
+        let runner = ConformanceRunner::new(transport);
+
+        let enabled = runner
+            .run_scenario("ws.feature_enabled_methods_advertised")
+            .expect("scenario should exist");
+        assert!(!enabled.passed);
+        assert_eq!(
+            enabled.detail,
+            "enabled feature methods missing from /info methods: voice/voice.start; enabled feature methods rejected as disabled or unknown: voice/voice.start: code=FEATURE_DISABLED"
+        );
+        let disabled = runner
+            .run_scenario("ws.feature_disabled_methods_rejected")
+            .expect("scenario should exist");
+        assert!(!disabled.passed);
+        assert_eq!(
+            disabled.detail,
+            "expected FEATURE_DISABLED for disabled feature methods, found canvas/chat.send: ok=Some(false), code=Some(\"INVALID_REQUEST\")"
+        );
+
+        let mut transport = passing_transport();
+        transport.features = None;
+        let runner = ConformanceRunner::new(transport);
+        for name in [
+            "ws.feature_disabled_methods_rejected",
+            "ws.feature_enabled_methods_advertised",
+        ] {
+            let outcome = runner.run_scenario(name).expect("scenario should exist");
+            assert_eq!(outcome.skipped, Some(SkipReason::NotApplicable));
+            assert_eq!(outcome.detail, "hello-ok carries no features map");
+        }
+    }
+
+    #[test]
+    fn runner_reconciles_published_self_assessment() {
+        let mut transport = passing_transport();
+        transport.self_assessment = Some(json!({
+            "tiers": ["required"],
+            "scenarios": ["ws.voice_call_connects"]
+        }));
+        transport.healthz = Some(json!({ "ok": false }));
+        let report = ConformanceRunner::new(transport).with_read_only(true).run();
+
+        let reconciliation = report
+            .self_assessment
+            .as_ref()
+            .expect("published self-assessment should be reconciled");
+        assert_eq!(reconciliation.contradicted, vec!["healthz.ok_true"]);
+        assert_eq!(
+            reconciliation.unclaimed_passes,
+            vec!["healthz.head_no_body", "healthz.date_within_clock_skew"]
+        );
+        assert!(reconciliation
+            .unverified
+            .contains(&"ws.channels_logout_account_persists".to_owned()));
+        assert_eq!(reconciliation.unknown, vec!["ws.voice_call_connects"]);
+        assert!(report
+            .to_markdown()
+            .contains("## Self-Assessment\n\n### Contradicted claims\n\n- `healthz.ok_true`\n"));
+        assert!(report.to_html().contains("<h3>Unknown claims</h3>"));
+
+        let mut transport = passing_transport();
+        transport.self_assessment = Some(json!({ "tiers": "required" }));
+        let report = ConformanceRunner::new(transport).run();
+        assert!(report.self_assessment.is_none());
+        assert!(report.warnings[0].starts_with(
+            "self-assessment at /.well-known/reclaw-conformance is invalid: invalid type"
+        ));
+        assert!(ConformanceRunner::new(passing_transport())
+            .run()
+            .self_assessment
+            .is_none());
+
+        let passed = ConformanceRunner::new(passing_transport())
+            .run()
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.passed)
+            .map(|outcome| outcome.name)
+            .collect::<Vec<_>>();
+        let mut transport = passing_transport();
+        transport.self_assessment = Some(json!({ "scenarios": passed }));
+        let report = ConformanceRunner::new(transport).run();
+        let reconciliation = report
+            .self_assessment
+            .expect("published self-assessment should be reconciled");
+        assert!(reconciliation.is_consistent(), "{reconciliation:?}");
+
+        let transport = Tweaked::new(passing_transport()).with_get_json(|inner, path| {
+            if path == SELF_ASSESSMENT_PATH {
+                return Err(TransportError::Status {
+                    status: 503,
+                    path: path.to_owned(),
+                });
+            }
+            inner.get_json(path)
+        });
+        let report = ConformanceRunner::new(transport).run();
+        assert!(report.self_assessment.is_none());
+        assert!(report.warnings.contains(
+            &"self-assessment at /.well-known/reclaw-conformance could not be fetched: unexpected status 503 for /.well-known/reclaw-conformance".to_owned()
+        ));
+    }
+
+    #[test]
+    fn missing_run_wait_reports_measured_duration_against_timing_band() {
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.timing]
+            early_ms = 0
+            late_ms = 10000
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = ConformanceRunner::new(passing_transport())
+            .with_config(config)
+            .run_scenario("ws.agent_wait_timeout_for_missing_run")
+            .expect("scenario should exist");
+
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert!(outcome
+            .detail
+            .contains("ms for expected 50ms, within band [50, 10050]ms)"));
+    }
+
+    #[test]
+    fn missing_run_wait_excludes_connection_setup_from_measurement() {
+        let setup = Rc::new(Cell::new(Duration::ZERO));
+        let handshakes = Rc::clone(&setup);
+        let transport = Tweaked::new(passing_transport())
+            .with_websocket_exchange(move |inner, frames| {
+                let handshake = Duration::from_millis(400);
+                thread::sleep(handshake);
+                handshakes.set(handshakes.get() + handshake);
+                inner.websocket_exchange(frames)
+            })
+            .with_connection_setup_time(move || setup.get());
+
+        let config = ConformanceConfig::from_toml(
+            r#"
+            [scenario.timing]
+            early_ms = 0
+            late_ms = 300
+            "#,
+        )
+        .expect("config should parse");
+        let outcome = ConformanceRunner::new(transport)
+            .with_config(config)
+            .run_scenario("ws.agent_wait_timeout_for_missing_run")
+            .expect("scenario should exist");
+
+        assert!(outcome.passed, "{}", outcome.detail);
+    }
+
+    #[test]
+    fn wait_without_run_id_fails_when_gateway_waits_instead_of_rejecting() {
+        let transport =
+            Tweaked::new(passing_transport()).with_websocket_exchange(|inner, frames| {
+                let mut responses = inner.websocket_exchange(frames)?;
+                responses[1] = json!({ "ok": true, "payload": { "status": "timeout" } });
+                responses[2] = json!({
+                    "ok": false,
+                    "error": { "code": "INVALID_REQUEST", "message": "bad params" }
+                });
+                Ok(responses)
+            });
+        let outcome = ConformanceRunner::new(transport)
+            .run_scenario("ws.agent_wait_rejects_missing_run_id")
+            .expect("scenario should exist");
+
+        assert!(!outcome.passed);
+        assert_eq!(
+            outcome.detail,
+            "missing runId: expected ok false, found status=Some(\"timeout\"); numeric runId: expected error.message to name runId, found Some(\"bad params\")"
+        );
+    }
+
+    #[test]
+    fn runner_runs_only_selected_scenarios_in_suite_order() {
+        let report = ConformanceRunner::new(passing_transport())
+            .with_selection(vec![
+                "ws.chat_abort_unknown_run_noop".to_owned(),
+                "run.identifier_isolation".to_owned(),
+                "healthz.ok_true".to_owned(),
+            ])
+            .run();
+
+        let names = report
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "healthz.ok_true",
+                "ws.chat_abort_unknown_run_noop",
+                "run.identifier_isolation"
+            ]
+        );
+        assert!(report.is_passing());
+    }
+
+    #[test]
+    fn runner_restarts_gateway_through_configured_hook() {
+        let runner = ConformanceRunner::new(passing_transport());
+        let outcome = runner
+            .run_scenario("restart.recovers_health_and_runs")
+            .expect("restart scenario should exist");
+        assert_eq!(outcome.skipped, Some(SkipReason::NotConfigured));
+
+        let marker = instance_file("restart");
+        let restart = |toml: &str| {
+            let config = ConformanceConfig::from_toml(toml).expect("config should parse");
+            let _ = std::fs::remove_file(&marker);
+            let transport = MockTransport {
+                instance_file: Some(marker.clone()),
+                ..passing_transport()
+            };
+            ConformanceRunner::new(transport)
+                .with_config(config)
+                .run_scenario("restart.recovers_health_and_runs")
+                .expect("restart scenario should exist")
+        };
+        let touch = format!(r#"hook = ["touch", "{}"]"#, marker.display());
+
+        let outcome = restart(&format!("[scenario.restart]\n{touch}"));
+        assert!(outcome.is_failure());
+        assert!(outcome
+            .detail
+            .starts_with("/info instance marker changed; healthz recovered after "));
+        assert!(outcome.detail.ends_with(
+            "but the completed run is not retrievable after the restart (agent.wait status=Some(\"timeout\"))"
+        ));
+
+        let outcome = restart(&format!(
+            "[scenario.restart]\n{touch}\nruns_persist = false"
+        ));
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert!(outcome
+            .detail
+            .contains("as documented by scenario.restart.runs_persist = false"));
+
+        let outcome = restart(
+            r#"
+            [scenario.restart]
+            hook = ["false"]
+            "#,
+        );
+        assert!(outcome.is_failure());
+        assert!(outcome
+            .detail
+            .starts_with("restart hook \"false\" exited with"));
+
+        let outcome = restart(
+            r#"
+            [scenario.restart]
+            hook = ["true"]
+            runs_persist = false
+            "#,
+        );
+        assert!(outcome.is_failure());
+        assert!(
+            outcome.detail.ends_with("so no restart was observed"),
+            "{}",
+            outcome.detail
+        );
+
+        let started = std::time::Instant::now();
+        let outcome = restart(
+            r#"
+            [scenario.restart]
+            hook = ["sh", "-c", "sleep 5 & echo gateway failed >&2; exit 1"]
+            "#,
+        );
+        assert!(outcome.is_failure());
+        assert!(
+            outcome.detail.ends_with(": gateway failed"),
+            "{}",
+            outcome.detail
+        );
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "a background process holding stderr must not block the runner"
+        );
+
+        let started = std::time::Instant::now();
+        let outcome = restart(
+            r#"
+            [scenario.restart]
+            hook = ["sleep", "5"]
+            hook_timeout_ms = 100
+            "#,
+        );
+        assert!(outcome.is_failure());
+        assert_eq!(
+            outcome.detail,
+            "restart hook \"sleep\" did not exit within 100ms and was killed"
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let _ = std::fs::remove_file(&marker);
+    }
+
+    #[test]
+    fn runner_audits_identifiers_echoed_across_scenarios() {
+        let first_run_id = RefCell::new(None::<Value>);
+        let stale_run_transport =
+            Tweaked::new(passing_transport()).with_websocket_exchange(move |inner, frames| {
+                let mut responses = inner.websocket_exchange(frames)?;
+                let mut first_run_id = first_run_id.borrow_mut();
+                match first_run_id.as_ref() {
+                    Some(stale) => {
+                        if let Some(last) = responses.last_mut() {
+                            last["staleRunId"] = stale.clone();
+                        }
+                    }
+                    None => {
+                        *first_run_id = frames
+                            .iter()
+                            .find_map(|frame| frame["params"].get("runId").cloned());
+                    }
+                }
+                Ok(responses)
+            });
+
+        let report = ConformanceRunner::new(passing_transport()).run();
+        let isolation = report.outcomes.last().expect("audit outcome should exist");
+        assert_eq!(isolation.name, "run.identifier_isolation");
+        assert!(isolation.passed, "{}", isolation.detail);
+
+        let report = ConformanceRunner::new(stale_run_transport).run();
+        let isolation = report.outcomes.last().expect("audit outcome should exist");
+        assert!(!isolation.passed);
+        assert!(isolation
+            .detail
+            .starts_with("gateway echoed identifiers across scenarios: "));
+        assert!(isolation.detail.contains("generated by ws."));
+        assert!(!isolation.notes.is_empty());
+
+        let runner = ConformanceRunner::new(passing_transport());
+        let standalone = runner
+            .run_scenario("run.identifier_isolation")
+            .expect("audit should be a catalogue scenario");
+        assert_eq!(standalone.skipped, Some(SkipReason::NotApplicable));
+        assert!(ConformanceRunner::<MockTransport>::catalogue()
+            .iter()
+            .any(|descriptor| descriptor.name == "run.identifier_isolation"));
+    }
+
+    #[test]
+    fn runner_stability_hash_tracks_normalized_responses() {
+        let first = ConformanceRunner::new(passing_transport()).run();
+        let second = ConformanceRunner::new(passing_transport()).run();
+        assert_eq!(first.stability_hash, second.stability_hash);
+        for (left, right) in first.outcomes.iter().zip(&second.outcomes) {
+            assert_eq!(left.stability_hash, right.stability_hash, "{}", left.name);
+        }
+
+        let mut transport = passing_transport();
+        transport.readyz = Some(json!({ "ok": true, "checks": ["db"] }));
+        let changed = ConformanceRunner::new(transport).run();
+        let hash_of = |report: &crate::ConformanceReport, name: &str| {
+            report
+                .outcomes
+                .iter()
+                .find(|outcome| outcome.name == name)
+                .and_then(|outcome| outcome.stability_hash.clone())
+        };
+        assert_ne!(first.stability_hash, changed.stability_hash);
+        assert_ne!(
+            hash_of(&first, "readyz.ok_true"),
+            hash_of(&changed, "readyz.ok_true")
+        );
+        assert_eq!(
+            hash_of(&first, "healthz.ok_true"),
+            hash_of(&changed, "healthz.ok_true")
+        );
+    }
+
+    #[test]
+    fn runner_profiles_transport_and_assertion_time() {
+        let report = ConformanceRunner::new(passing_transport()).run();
+        for outcome in &report.outcomes {
+            assert!(outcome.timing.transport_us <= outcome.timing.total_us);
+            assert_eq!(outcome.timing.setup_us, 0);
+        }
+
+        let table = report.profile_table();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("Scenario"));
+        assert_eq!(lines.len(), report.total + 2);
+        assert!(lines[report.total + 1].starts_with("total "));
+        let totals = lines[1..=report.total]
+            .iter()
+            .map(|line| {
+                line.split_whitespace()
+                    .nth(1)
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .expect("total column should be numeric")
+            })
+            .collect::<Vec<_>>();
+        assert!(totals.windows(2).all(|pair| pair[0] >= pair[1]));
     }
 }
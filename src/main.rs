@@ -1,16 +1,124 @@
-use std::process::ExitCode;
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, BufRead, IsTerminal},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
 
-use clap::Parser;
-use reclaw_conformance::{ConformanceRunner, HttpTransport};
+use clap::{Parser, Subcommand};
+use inquire::MultiSelect;
+use reclaw_conformance::{
+    target_metadata, CertificationPackage, ConformanceConfig, ConformanceRunner,
+    ConformanceTransport, FixLoop, FixLoopTrigger, HttpTransport, IpVersion, MatrixReport,
+    MatrixTargets, ScenarioDescriptor, Severity,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "reclaw-conformance", version)]
 struct Args {
-    #[arg(long, default_value = "http://127.0.0.1:18789")]
+    #[arg(long, global = true, default_value = "http://127.0.0.1:18789")]
     base_url: String,
 
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Address family used for every HTTP and WS connection: 4, 6, or auto.
+    #[arg(long, global = true, default_value_t = IpVersion::Auto)]
+    ip_version: IpVersion,
+
+    /// Connect to this address of the target host instead of resolving it.
+    #[arg(long, global = true)]
+    pin_address: Option<IpAddr>,
+
+    /// Skip scenarios that start runs, send messages, or log out accounts.
+    #[arg(long, global = true)]
+    read_only: bool,
+
     #[arg(long)]
     json: bool,
+
+    #[arg(long, conflicts_with = "json")]
+    markdown: bool,
+
+    /// After the run, print per-scenario durations to stderr, slowest first.
+    #[arg(long)]
+    profile_scenarios: bool,
+
+    /// Pick the tags and scenarios to run from a searchable list before the run.
+    #[arg(long)]
+    interactive: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Re-run one scenario on Enter or when the target version reported by /info changes.
+    Fixloop {
+        scenario: String,
+
+        #[arg(long, default_value_t = 2000)]
+        poll_ms: u64,
+    },
+    /// Run the suite and bundle reports, transcripts, and target metadata into a zip archive.
+    Certify {
+        #[arg(long)]
+        out: PathBuf,
+
+        /// File holding the HMAC-SHA256 key used to sign report.json.
+        #[arg(long)]
+        signing_key: Option<PathBuf>,
+
+        /// Combined size limit for transcript captures, split evenly between scenarios while
+        /// capturing; payloads past a scenario's share are kept as truncation markers.
+        #[arg(long, default_value_t = 256)]
+        max_transcript_mb: u64,
+    },
+    /// Run the suite against every gateway build in a targets file and report a scenario × target grid.
+    Matrix {
+        #[arg(long)]
+        targets: PathBuf,
+
+        /// Targets run at the same time; 1 runs them serially.
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+
+        #[arg(long)]
+        html: Option<PathBuf>,
+
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// First flag given that only the default suite run reads; subcommands reject it.
+    fn suite_flag(&self) -> Option<&'static str> {
+        [
+            ("--json", self.json),
+            ("--markdown", self.markdown),
+            ("--profile-scenarios", self.profile_scenarios),
+            ("--interactive", self.interactive),
+        ]
+        .into_iter()
+        .find_map(|(flag, set)| set.then_some(flag))
+    }
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Fixloop { .. } => "fixloop",
+            Self::Certify { .. } => "certify",
+            Self::Matrix { .. } => "matrix",
+        }
+    }
 }
 
 fn main() -> ExitCode {
@@ -25,22 +133,160 @@ fn main() -> ExitCode {
 
 fn run() -> Result<ExitCode, String> {
     let args = Args::parse();
-    let transport = HttpTransport::new(args.base_url).map_err(|error| error.to_string())?;
-    let report = ConformanceRunner::new(transport).run();
+    if let (Some(command), Some(flag)) = (&args.command, args.suite_flag()) {
+        return Err(format!(
+            "{flag} applies to suite runs only and cannot be used with {}",
+            command.name()
+        ));
+    }
+    let config = match &args.config {
+        Some(path) => ConformanceConfig::load(path).map_err(|error| error.to_string())?,
+        None => ConformanceConfig::default(),
+    };
+    match &args.command {
+        Some(Command::Matrix {
+            targets,
+            concurrency,
+            html,
+            json,
+        }) => run_matrix(
+            &args,
+            &config,
+            targets,
+            *concurrency,
+            html.as_deref(),
+            json.as_deref(),
+        ),
+        Some(Command::Fixloop { scenario, poll_ms }) => {
+            let (runner, _) = single_target_runner(&args, config)?;
+            run_fixloop(runner, scenario.clone(), Duration::from_millis(*poll_ms))
+        }
+        Some(Command::Certify {
+            out,
+            signing_key,
+            max_transcript_mb,
+        }) => {
+            let (runner, network) = single_target_runner(&args, config)?;
+            run_certify(
+                runner,
+                &args.base_url,
+                network,
+                out,
+                signing_key.as_deref(),
+                *max_transcript_mb,
+            )
+        }
+        None => {
+            let (mut runner, _) = single_target_runner(&args, config)?;
+            if args.interactive {
+                runner = runner.with_selection(select_scenarios()?);
+            }
+            run_suite(runner, args.json, args.markdown, args.profile_scenarios)
+        }
+    }
+}
+
+/// Builds the runner for `--base-url` along with the network path it will use.
+fn single_target_runner(
+    args: &Args,
+    config: ConformanceConfig,
+) -> Result<(ConformanceRunner<HttpTransport>, serde_json::Value), String> {
+    let transport = HttpTransport::new(args.base_url.clone())
+        .and_then(|transport| transport.with_network(args.ip_version, args.pin_address))
+        .and_then(|transport| transport.with_trace(&config))
+        .map(|transport| transport.with_read_timeout(config.ws_read_timeout()))
+        .map_err(|error| error.to_string())?;
+    if let Some(trace_id) = transport.trace_id() {
+        eprintln!("trace id: {trace_id}");
+    }
+    let network = serde_json::json!({
+        "ipVersion": transport.ip_version().as_str(),
+        "connectAddress": transport.connect_address().map(|address| address.to_string()),
+        "traceId": transport.trace_id(),
+    });
+    let runner = ConformanceRunner::new(transport)
+        .with_config(config)
+        .with_read_only(args.read_only);
+    Ok((runner, network))
+}
+
+fn run_suite<T: ConformanceTransport>(
+    runner: ConformanceRunner<T>,
+    json: bool,
+    markdown: bool,
+    profile: bool,
+) -> Result<ExitCode, String> {
+    let report = runner.run();
 
-    if args.json {
+    if json {
         let text = serde_json::to_string_pretty(&report)
             .map_err(|error| format!("failed to serialize JSON report: {error}"))?;
         println!("{text}");
+    } else if markdown {
+        print!("{}", report.to_markdown());
     } else {
         println!(
-            "scenarios: {} total, {} failed",
-            report.total, report.failed
+            "scenarios: {} total, {} failed, {} skipped",
+            report.total, report.failed, report.skipped
         );
+        println!(
+            "payload bytes: {} sent, {} received",
+            report.bytes_sent, report.bytes_received
+        );
+        println!("stability hash: {}", report.stability_hash);
+        for warning in &report.warnings {
+            println!("[WARN] {warning}");
+        }
         for outcome in &report.outcomes {
-            let status = if outcome.passed { "PASS" } else { "FAIL" };
-            println!("[{status}] {} - {}", outcome.name, outcome.detail);
+            let status = match outcome.skipped {
+                Some(_) => "SKIP",
+                None if outcome.passed => "PASS",
+                None => "FAIL",
+            };
+            let mut notes = Vec::new();
+            if let Some(reason) = outcome.skipped {
+                notes.push(reason.as_str().to_owned());
+            }
+            if outcome.severity == Severity::Recommended {
+                notes.push(outcome.severity.as_str().to_owned());
+            }
+            if let Some(owner) = &outcome.owner {
+                notes.push(format!("owner: {owner}"));
+            }
+            if let Some(code) = &outcome.retried_after {
+                notes.push(format!("retried after {code}"));
+            }
+            if notes.is_empty() {
+                println!("[{status}] {} - {}", outcome.name, outcome.detail);
+            } else {
+                println!(
+                    "[{status}] {} ({}) - {}",
+                    outcome.name,
+                    notes.join(", "),
+                    outcome.detail
+                );
+            }
+            if outcome.is_failure() {
+                for note in &outcome.notes {
+                    println!("    {note}");
+                }
+            }
         }
+        if let Some(reconciliation) = &report.self_assessment {
+            println!("self-assessment:");
+            if reconciliation.is_consistent() {
+                println!("    published claims match the observed results");
+            }
+            for (heading, names) in reconciliation.sections() {
+                for name in names {
+                    println!("    [{}] {name}", heading.to_lowercase());
+                }
+            }
+        }
+    }
+
+    if profile {
+        eprint!("{}", report.profile_table());
     }
 
     if report.is_passing() {
@@ -49,3 +295,199 @@ fn run() -> Result<ExitCode, String> {
         Ok(ExitCode::from(1))
     }
 }
+
+enum Choice {
+    Tag(&'static str, usize),
+    Scenario(ScenarioDescriptor),
+}
+
+impl fmt::Display for Choice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag, count) => write!(f, "tag:{tag} ({count} scenarios)"),
+            Self::Scenario(descriptor) => {
+                write!(f, "{} [{}]", descriptor.name, descriptor.tags.join(", "))
+            }
+        }
+    }
+}
+
+fn select_scenarios() -> Result<Vec<String>, String> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err("--interactive needs an interactive terminal".to_owned());
+    }
+
+    let catalogue = ConformanceRunner::<HttpTransport>::catalogue();
+    let mut tags = BTreeMap::<&'static str, usize>::new();
+    for descriptor in &catalogue {
+        for tag in descriptor.tags {
+            *tags.entry(tag).or_default() += 1;
+        }
+    }
+    let mut choices = tags
+        .into_iter()
+        .map(|(tag, count)| Choice::Tag(tag, count))
+        .collect::<Vec<_>>();
+    choices.extend(catalogue.into_iter().map(Choice::Scenario));
+
+    let selected = MultiSelect::new("Scenarios to run:", choices)
+        .with_page_size(15)
+        .with_help_message("type to filter, space to toggle, enter to run")
+        .prompt()
+        .map_err(|error| format!("scenario selection failed: {error}"))?;
+
+    let catalogue = ConformanceRunner::<HttpTransport>::catalogue();
+    let names = catalogue
+        .iter()
+        .filter(|descriptor| {
+            selected.iter().any(|choice| match choice {
+                Choice::Tag(tag, _) => descriptor.tags.contains(tag),
+                Choice::Scenario(selected) => selected.name == descriptor.name,
+            })
+        })
+        .map(|descriptor| descriptor.name.to_owned())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Err("no scenarios selected".to_owned());
+    }
+    Ok(names)
+}
+
+fn run_matrix(
+    args: &Args,
+    config: &ConformanceConfig,
+    targets: &Path,
+    concurrency: usize,
+    html: Option<&Path>,
+    json: Option<&Path>,
+) -> Result<ExitCode, String> {
+    if args.pin_address.is_some() {
+        return Err(
+            "--pin-address names one host's address and cannot apply to every matrix target"
+                .to_owned(),
+        );
+    }
+    let targets = MatrixTargets::load(targets).map_err(|error| error.to_string())?;
+    let matrix = MatrixReport::collect(&targets, concurrency, |target| {
+        let transport = HttpTransport::new(target.base_url.clone())
+            .and_then(|transport| transport.with_network(args.ip_version, None))
+            .and_then(|transport| transport.with_trace(config))
+            .map(|transport| transport.with_read_timeout(config.ws_read_timeout()))
+            .map_err(|error| error.to_string())?;
+        Ok(ConformanceRunner::new(transport)
+            .with_config(config.clone())
+            .with_read_only(args.read_only)
+            .run())
+    });
+
+    if let Some(path) = html {
+        fs::write(path, matrix.to_html())
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+    }
+    if let Some(path) = json {
+        let text = serde_json::to_string_pretty(&matrix)
+            .map_err(|error| format!("failed to serialize JSON matrix: {error}"))?;
+        fs::write(path, text)
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+    }
+    print!("{}", matrix.to_text());
+
+    if matrix.is_passing() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(1))
+    }
+}
+
+fn run_certify<T: ConformanceTransport>(
+    runner: ConformanceRunner<T>,
+    base_url: &str,
+    network: serde_json::Value,
+    out: &Path,
+    signing_key: Option<&Path>,
+    max_transcript_mb: u64,
+) -> Result<ExitCode, String> {
+    let max_transcript_bytes = max_transcript_mb.saturating_mul(1024 * 1024);
+    let scenarios = ConformanceRunner::<T>::scenario_names().len() as u64;
+    let runner = runner
+        .with_transcripts(true)
+        .with_max_capture_bytes(max_transcript_bytes / scenarios);
+    let mut target = target_metadata(runner.transport(), base_url);
+    target["network"] = network;
+    let report = runner.run();
+
+    let mut package =
+        CertificationPackage::new(&report, target).with_max_transcript_bytes(max_transcript_bytes);
+    if let Some(path) = signing_key {
+        let key = fs::read(path)
+            .map_err(|error| format!("failed to read signing key {}: {error}", path.display()))?;
+        package = package.with_signing_key(key);
+    }
+    let entries = package.write_zip(out).map_err(|error| error.to_string())?;
+
+    println!(
+        "certification package {}: {} entries, {} scenarios, {} failed",
+        out.display(),
+        entries.len(),
+        report.total,
+        report.failed
+    );
+
+    if report.is_passing() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(1))
+    }
+}
+
+fn run_fixloop<T: ConformanceTransport>(
+    runner: ConformanceRunner<T>,
+    scenario: String,
+    poll_interval: Duration,
+) -> Result<ExitCode, String> {
+    let mut fixloop = FixLoop::new(runner, scenario)?;
+    let mut version = fixloop.target_version();
+
+    let (keypress_tx, keypress_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(text) = line else { break };
+            let quit = text.trim() == "q";
+            if keypress_tx.send(quit).is_err() || quit {
+                break;
+            }
+        }
+    });
+
+    println!("fixloop: press Enter to re-run, q to quit");
+    let mut last = fixloop.attempt(FixLoopTrigger::Initial);
+    println!("{}", last.render());
+
+    loop {
+        let trigger = match keypress_rx.recv_timeout(poll_interval) {
+            Ok(true) => break,
+            Ok(false) => FixLoopTrigger::Keypress,
+            Err(error) => {
+                if error == RecvTimeoutError::Disconnected {
+                    // stdin is closed; keep watching the target version only.
+                    thread::sleep(poll_interval);
+                }
+                let current = fixloop.target_version();
+                if current == version {
+                    continue;
+                }
+                let previous = std::mem::replace(&mut version, current.clone());
+                FixLoopTrigger::TargetVersionChanged { previous, current }
+            }
+        };
+
+        last = fixloop.attempt(trigger);
+        println!("{}", last.render());
+    }
+
+    if !last.outcome.is_failure() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(1))
+    }
+}
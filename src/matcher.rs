@@ -0,0 +1,172 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Assertion on a response value whose exact content is not deterministic, such as real agent
+/// output or server timestamps.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "MatcherSpec")]
+pub enum Matcher {
+    /// String value matching the pattern anywhere, unless anchored.
+    Regex(Regex),
+    /// Number within the inclusive bounds; a missing bound is unbounded.
+    NumericRange { min: Option<f64>, max: Option<f64> },
+    /// Value equal to one of the listed values.
+    OneOf(Vec<Value>),
+    /// Unix millisecond timestamp within `ms` of the runner clock.
+    TimestampWithin { ms: u64 },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum MatcherSpec {
+    Regex { pattern: String },
+    NumericRange { min: Option<f64>, max: Option<f64> },
+    OneOf { values: Vec<Value> },
+    TimestampWithin { ms: u64 },
+}
+
+impl TryFrom<MatcherSpec> for Matcher {
+    type Error = String;
+
+    fn try_from(spec: MatcherSpec) -> Result<Self, Self::Error> {
+        match spec {
+            MatcherSpec::Regex { pattern } => Self::regex(&pattern),
+            MatcherSpec::NumericRange { min, max } => Ok(Self::NumericRange { min, max }),
+            MatcherSpec::OneOf { values } => Ok(Self::OneOf(values)),
+            MatcherSpec::TimestampWithin { ms } => Ok(Self::TimestampWithin { ms }),
+        }
+    }
+}
+
+impl Matcher {
+    pub fn regex(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern)
+            .map(Self::Regex)
+            .map_err(|error| format!("invalid regex {pattern:?}: {error}"))
+    }
+
+    pub fn equals(value: impl Into<Value>) -> Self {
+        Self::OneOf(vec![value.into()])
+    }
+
+    /// `Ok` when `value` matches; otherwise a description of the mismatch.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        match self {
+            Self::Regex(regex) => match value.as_str() {
+                Some(text) if regex.is_match(text) => Ok(()),
+                Some(_) => Err(format!("{value} does not match /{}/", regex.as_str())),
+                None => Err(format!("{value} is not a string")),
+            },
+            Self::NumericRange { min, max } => {
+                let Some(number) = value.as_f64() else {
+                    return Err(format!("{value} is not a number"));
+                };
+                if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                    Err(format!(
+                        "{value} is outside [{}, {}]",
+                        min.map_or("-inf".to_owned(), |min| min.to_string()),
+                        max.map_or("inf".to_owned(), |max| max.to_string())
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            Self::OneOf(values) => {
+                if values.contains(value) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{value} is not one of {}",
+                        Value::from(values.clone())
+                    ))
+                }
+            }
+            Self::TimestampWithin { ms } => {
+                let Some(timestamp) = value.as_i64() else {
+                    return Err(format!("{value} is not a Unix millisecond timestamp"));
+                };
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|value| value.as_millis() as i128)
+                    .unwrap_or(0);
+                let skew = (i128::from(timestamp) - now).abs();
+                if skew <= i128::from(*ms) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{value} is {skew}ms from the runner clock, beyond {ms}ms"
+                    ))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::Matcher;
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        output: Matcher,
+        tokens: Matcher,
+        status: Matcher,
+        ts: Matcher,
+    }
+
+    #[test]
+    fn matchers_parse_from_toml_and_check_values() {
+        let fixture: Fixture = toml::from_str(
+            r#"
+            output = { kind = "regex", pattern = "^Echo: .+" }
+            tokens = { kind = "numeric_range", min = 1, max = 4096 }
+            status = { kind = "one_of", values = ["completed", "aborted"] }
+            ts = { kind = "timestamp_within", ms = 60000 }
+            "#,
+        )
+        .expect("matchers should parse");
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock after epoch")
+            .as_millis() as i64;
+
+        assert!(fixture.output.check(&json!("Echo: hi")).is_ok());
+        assert_eq!(
+            fixture.output.check(&json!("hi")),
+            Err("\"hi\" does not match /^Echo: .+/".to_owned())
+        );
+        assert!(fixture.output.check(&json!(null)).is_err());
+        assert!(fixture.tokens.check(&json!(12)).is_ok());
+        assert_eq!(
+            fixture.tokens.check(&json!(0)),
+            Err("0 is outside [1, 4096]".to_owned())
+        );
+        assert!(fixture.status.check(&json!("aborted")).is_ok());
+        assert!(fixture.status.check(&json!("queued")).is_err());
+        assert!(fixture.ts.check(&json!(now - 1000)).is_ok());
+        assert!(fixture.ts.check(&json!(now - 120_000)).is_err());
+    }
+
+    #[test]
+    fn regex_matcher_rejects_invalid_pattern() {
+        let error = toml::from_str::<Fixture>(
+            r#"
+            output = { kind = "regex", pattern = "(" }
+            tokens = { kind = "one_of", values = [] }
+            status = { kind = "one_of", values = [] }
+            ts = { kind = "one_of", values = [] }
+            "#,
+        )
+        .err()
+        .expect("pattern should be rejected");
+        assert!(error.to_string().contains("invalid regex \"(\""));
+    }
+}
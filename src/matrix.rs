@@ -0,0 +1,331 @@
+use std::{fs, path::Path, thread};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{report::html_escape, ConfigError, ConformanceReport, SkipReason};
+
+/// Gateway builds to run the suite against, loaded from `targets.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatrixTargets {
+    pub targets: Vec<MatrixTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatrixTarget {
+    /// Column label, usually the gateway build or version.
+    pub name: String,
+    pub base_url: String,
+}
+
+impl MatrixTargets {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|error| ConfigError::Read(format!("{}: {error}", path.display())))?;
+        Self::from_toml(&text)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let targets: Self =
+            toml::from_str(text).map_err(|error| ConfigError::Parse(error.to_string()))?;
+        if targets.targets.is_empty() {
+            return Err(ConfigError::Parse("no [[targets]] listed".to_owned()));
+        }
+        Ok(targets)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixColumn {
+    #[serde(flatten)]
+    pub target: MatrixTarget,
+    pub total: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub passing: bool,
+    pub stability_hash: Option<String>,
+    /// Set when the suite could not run against the target at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixCell {
+    /// Whether the scenario ran and did not pass; skipped cells are not failures.
+    pub failed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<SkipReason>,
+    pub detail: String,
+}
+
+impl MatrixCell {
+    fn status(&self) -> String {
+        match self.skipped {
+            Some(reason) => format!("SKIP ({})", reason.as_str()),
+            None if self.failed => "FAIL".to_owned(),
+            None => "PASS".to_owned(),
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self.skipped {
+            Some(_) => "skip",
+            None if self.failed => "fail",
+            None => "pass",
+        }
+    }
+}
+
+/// One row per scenario; `cells[i]` is the result against `targets[i]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixRow {
+    pub scenario: &'static str,
+    pub cells: Vec<Option<MatrixCell>>,
+}
+
+/// Scenario × target grid consolidating one suite run per gateway build.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixReport {
+    pub targets: Vec<MatrixColumn>,
+    pub rows: Vec<MatrixRow>,
+}
+
+impl MatrixReport {
+    /// Runs `run_target` for every target, at most `concurrency` at a time, keeping target order.
+    pub fn collect<F>(targets: &MatrixTargets, concurrency: usize, run_target: F) -> Self
+    where
+        F: Fn(&MatrixTarget) -> Result<ConformanceReport, String> + Sync,
+    {
+        let mut results = Vec::with_capacity(targets.targets.len());
+        for batch in targets.targets.chunks(concurrency.max(1)) {
+            let run_target = &run_target;
+            thread::scope(|scope| {
+                let handles = batch
+                    .iter()
+                    .map(|target| scope.spawn(move || run_target(target)))
+                    .collect::<Vec<_>>();
+                for handle in handles {
+                    results.push(
+                        handle
+                            .join()
+                            .unwrap_or_else(|_| Err("target run panicked".to_owned())),
+                    );
+                }
+            });
+        }
+
+        Self::new(targets.targets.iter().cloned().zip(results).collect())
+    }
+
+    pub fn new(results: Vec<(MatrixTarget, Result<ConformanceReport, String>)>) -> Self {
+        let mut rows = Vec::<MatrixRow>::new();
+        for (index, (_, result)) in results.iter().enumerate() {
+            let Ok(report) = result else { continue };
+            for outcome in &report.outcomes {
+                let row = match rows.iter().position(|row| row.scenario == outcome.name) {
+                    Some(position) => &mut rows[position],
+                    None => {
+                        rows.push(MatrixRow {
+                            scenario: outcome.name,
+                            cells: vec![None; results.len()],
+                        });
+                        rows.last_mut().expect("row was just pushed")
+                    }
+                };
+                row.cells[index] = Some(MatrixCell {
+                    failed: outcome.is_failure(),
+                    skipped: outcome.skipped,
+                    detail: outcome.detail.clone(),
+                });
+            }
+        }
+
+        let targets = results
+            .into_iter()
+            .map(|(target, result)| match result {
+                Ok(report) => MatrixColumn {
+                    target,
+                    total: report.total,
+                    failed: report.failed,
+                    skipped: report.skipped,
+                    passing: report.is_passing(),
+                    stability_hash: Some(report.stability_hash),
+                    error: None,
+                },
+                Err(error) => MatrixColumn {
+                    target,
+                    total: 0,
+                    failed: 0,
+                    skipped: 0,
+                    passing: false,
+                    stability_hash: None,
+                    error: Some(error),
+                },
+            })
+            .collect();
+
+        Self { targets, rows }
+    }
+
+    pub fn is_passing(&self) -> bool {
+        self.targets.iter().all(|target| target.passing)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = self
+            .targets
+            .iter()
+            .map(|column| match &column.error {
+                Some(error) => format!("{}: error - {error}", column.target.name),
+                None => format!(
+                    "{}: {} total, {} failed, {} skipped",
+                    column.target.name, column.total, column.failed, column.skipped
+                ),
+            })
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            let cells = row
+                .cells
+                .iter()
+                .map(|cell| cell.as_ref().map_or("-".to_owned(), MatrixCell::status))
+                .collect::<Vec<_>>();
+            lines.push(format!("{} | {}", row.scenario, cells.join(" | ")));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    pub fn to_html(&self) -> String {
+        let headers = self
+            .targets
+            .iter()
+            .map(|column| {
+                let summary = match &column.error {
+                    Some(error) => format!("error: {error}"),
+                    None => format!("{} failed, {} skipped", column.failed, column.skipped),
+                };
+                format!(
+                    "<th>{}<br><small>{}</small></th>",
+                    html_escape(&column.target.name),
+                    html_escape(&summary)
+                )
+            })
+            .collect::<String>();
+
+        let mut rows = String::new();
+        for row in &self.rows {
+            rows.push_str(&format!(
+                "<tr><td><code>{}</code></td>",
+                html_escape(row.scenario)
+            ));
+            for cell in &row.cells {
+                match cell {
+                    Some(cell) => rows.push_str(&format!(
+                        "<td class=\"{}\" title=\"{}\">{}</td>",
+                        cell.class(),
+                        html_escape(&cell.detail),
+                        cell.status()
+                    )),
+                    None => rows.push_str("<td>-</td>"),
+                }
+            }
+            rows.push_str("</tr>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Conformance Matrix</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+td.pass {{ color: #1a7f37; }}
+td.fail {{ color: #cf222e; }}
+td.skip {{ color: #6e7781; }}
+</style>
+</head>
+<body>
+<h1>Conformance Matrix</h1>
+<table>
+<tr><th>Scenario</th>{headers}</tr>
+{rows}</table>
+</body>
+</html>
+"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConformanceOutcome, ConformanceReport};
+
+    use super::{MatrixReport, MatrixTargets};
+
+    #[test]
+    fn collect_builds_scenario_by_target_grid_in_target_order() {
+        let targets = MatrixTargets::from_toml(
+            r#"
+            [[targets]]
+            name = "1.4.0"
+            base_url = "http://a"
+
+            [[targets]]
+            name = "1.5.0-rc1"
+            base_url = "http://b"
+
+            [[targets]]
+            name = "offline"
+            base_url = "http://c"
+            "#,
+        )
+        .expect("targets should parse");
+
+        let matrix = MatrixReport::collect(&targets, 2, |target| match target.base_url.as_str() {
+            "http://a" => Ok(ConformanceReport::new(vec![ConformanceOutcome::pass(
+                "healthz.ok_true",
+                "ok",
+            )])),
+            "http://b" => Ok(ConformanceReport::new(vec![ConformanceOutcome::fail(
+                "healthz.ok_true",
+                "not ok",
+            )])),
+            _ => Err("connection refused".to_owned()),
+        });
+
+        let names = matrix
+            .targets
+            .iter()
+            .map(|column| column.target.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["1.4.0", "1.5.0-rc1", "offline"]);
+        assert_eq!(matrix.rows.len(), 1);
+        let cells = &matrix.rows[0].cells;
+        assert!(cells[0].as_ref().is_some_and(|cell| !cell.failed));
+        assert!(cells[1].as_ref().is_some_and(|cell| cell.failed));
+        assert!(cells[2].is_none());
+        assert_eq!(
+            matrix.targets[2].error.as_deref(),
+            Some("connection refused")
+        );
+        assert!(!matrix.is_passing());
+        assert!(matrix
+            .to_html()
+            .contains("<td class=\"fail\" title=\"not ok\">FAIL</td>"));
+        assert_eq!(
+            matrix.to_text().lines().nth(3),
+            Some("healthz.ok_true | PASS | FAIL | -")
+        );
+    }
+
+    #[test]
+    fn from_toml_requires_targets() {
+        let error = MatrixTargets::from_toml("").expect_err("should fail");
+        assert_eq!(error.to_string(), "invalid config: no [[targets]] listed");
+    }
+}
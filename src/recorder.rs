@@ -0,0 +1,410 @@
+use std::{
+    cell::RefCell,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{ConformanceTransport, RawRequest, RawResponse, TransportError};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptEntry {
+    pub operation: &'static str,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioTranscript {
+    pub scenario: &'static str,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// Size of one response payload, labelled with the request that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResponseSize {
+    pub(crate) label: String,
+    pub(crate) bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Recording {
+    pub(crate) entries: Vec<TranscriptEntry>,
+    pub(crate) bytes_sent: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) responses: Vec<ResponseSize>,
+    /// Every payload received, kept for the cross-scenario identifier audit.
+    pub(crate) received: Vec<Value>,
+    /// Time spent inside the wrapped transport.
+    pub(crate) transport_time: Duration,
+    /// Part of `transport_time` the wrapped transport spent establishing connections.
+    pub(crate) setup_time: Duration,
+    /// Bytes of payloads kept in full, charged against the capture budget.
+    pub(crate) retained_bytes: u64,
+    /// `DUPLICATE` or `CONFLICT` error codes, each with the params of the request it answered.
+    pub(crate) collisions: Vec<(String, Value)>,
+}
+
+impl Recording {
+    /// Folds in an earlier attempt's transcript, metering and timing. Its received payloads are
+    /// dropped so the stability hash and identifier audit reflect the reported attempt only.
+    pub(crate) fn absorb(&mut self, earlier: Recording) {
+        self.entries.splice(0..0, earlier.entries);
+        self.bytes_sent += earlier.bytes_sent;
+        self.bytes_received += earlier.bytes_received;
+        self.responses.splice(0..0, earlier.responses);
+        self.transport_time += earlier.transport_time;
+        self.setup_time += earlier.setup_time;
+    }
+
+    /// Hash of every received payload after normalization, so runs against the same gateway
+    /// build can be compared without transcripts.
+    ///
+    /// Normalization replaces `volatile` (the run-specific part of generated identifiers) with a
+    /// placeholder and drops values of timestamp-like keys (`ts`, `*Ms`, `*At`).
+    pub(crate) fn stability_hash(&self, volatile: &str) -> String {
+        let normalized = self
+            .received
+            .iter()
+            .map(|payload| normalize(payload, volatile))
+            .collect::<Vec<_>>();
+        let bytes = serde_json::to_vec(&normalized).unwrap_or_default();
+        Sha256::digest(&bytes)[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+fn normalize(value: &Value, volatile: &str) -> Value {
+    match value {
+        Value::String(text) if !volatile.is_empty() => {
+            Value::String(text.replace(volatile, "<run>"))
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| normalize(item, volatile)).collect())
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, item)| {
+                    let item = if key == "ts" || key.ends_with("Ms") || key.ends_with("At") {
+                        Value::Null
+                    } else {
+                        normalize(item, volatile)
+                    };
+                    (key.replace(volatile, "<run>"), item)
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Stand-in for a payload that was not kept, identifying it by size and digest.
+pub(crate) fn truncation_marker(value: &Value) -> Value {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    let sha256 = Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    serde_json::json!({
+        "truncated": true,
+        "originalBytes": bytes.len(),
+        "sha256": sha256,
+    })
+}
+
+/// Wraps a transport for the duration of one scenario, metering payload bytes and optionally
+/// recording every exchange.
+///
+/// Payloads kept for the transcript and the identifier audit are charged against `budget`;
+/// once it is spent, later payloads are kept as truncation markers instead.
+pub(crate) struct RecordingTransport<'a, T> {
+    inner: &'a T,
+    capture: bool,
+    budget: u64,
+    recording: RefCell<Recording>,
+}
+
+impl<'a, T> RecordingTransport<'a, T>
+where
+    T: ConformanceTransport,
+{
+    pub(crate) fn new(inner: &'a T, capture: bool, budget: u64) -> Self {
+        Self {
+            inner,
+            capture,
+            budget,
+            recording: RefCell::new(Recording::default()),
+        }
+    }
+
+    pub(crate) fn finish(self) -> Recording {
+        self.recording.into_inner()
+    }
+
+    fn timed<R>(&self, call: impl FnOnce(&T) -> R) -> R {
+        // Drop sizes left over from calls made outside this recorder.
+        self.inner.take_response_sizes();
+        let setup_before = self.inner.connection_setup_time();
+        let started = Instant::now();
+        let result = call(self.inner);
+        let elapsed = started.elapsed();
+        let setup = self
+            .inner
+            .connection_setup_time()
+            .saturating_sub(setup_before);
+
+        let mut recording = self.recording.borrow_mut();
+        recording.transport_time += elapsed;
+        recording.setup_time += setup;
+        result
+    }
+
+    /// Wire sizes of `payloads` as the inner transport measured them, or their encoded JSON
+    /// length when it cannot.
+    fn response_sizes(&self, payloads: &[&Value]) -> Vec<u64> {
+        match self.inner.take_response_sizes() {
+            Some(sizes) if sizes.len() == payloads.len() => sizes,
+            _ => payloads.iter().map(|payload| json_len(payload)).collect(),
+        }
+    }
+
+    fn meter_sent(&self, payload: &Value) {
+        self.recording.borrow_mut().bytes_sent += json_len(payload);
+    }
+
+    fn note_collision(&self, request: &Value, response: &Value) {
+        let code = response
+            .get("error")
+            .and_then(|error| error.get("code"))
+            .and_then(Value::as_str);
+        if let Some(code @ ("DUPLICATE" | "CONFLICT")) = code {
+            let params = request.get("params").unwrap_or(request);
+            self.recording
+                .borrow_mut()
+                .collisions
+                .push((code.to_owned(), params.clone()));
+        }
+    }
+
+    fn retain(&self, payload: Value) -> Value {
+        let bytes = json_len(&payload);
+        let mut recording = self.recording.borrow_mut();
+        if recording.retained_bytes.saturating_add(bytes) > self.budget {
+            return truncation_marker(&payload);
+        }
+        recording.retained_bytes += bytes;
+        payload
+    }
+
+    fn meter_received(&self, label: String, bytes: u64, payload: Value) {
+        let payload = self.retain(payload);
+        let mut recording = self.recording.borrow_mut();
+        recording.bytes_received += bytes;
+        recording.responses.push(ResponseSize { label, bytes });
+        recording.received.push(payload);
+    }
+
+    fn record(
+        &self,
+        operation: &'static str,
+        path: &str,
+        request: Option<Value>,
+        result: Result<(Option<u16>, Value), &TransportError>,
+    ) {
+        if !self.capture {
+            return;
+        }
+
+        let request = request.map(|request| self.retain(request));
+        let (status, response, error) = match result {
+            Ok((status, response)) => (status, Some(self.retain(response)), None),
+            Err(error) => (None, None, Some(error.to_string())),
+        };
+        self.recording.borrow_mut().entries.push(TranscriptEntry {
+            operation,
+            path: path.to_owned(),
+            request,
+            status,
+            response,
+            error,
+        });
+    }
+
+    fn record_raw(
+        &self,
+        address: Option<IpAddr>,
+        request: &RawRequest,
+        result: &Result<RawResponse, TransportError>,
+    ) {
+        self.recording.borrow_mut().bytes_sent += request.body.as_ref().map_or(0, Vec::len) as u64;
+        if let Ok(response) = result {
+            self.meter_received(
+                format!("{} {}", request.method, request.path),
+                response.body.len() as u64,
+                response.json().unwrap_or_else(|| {
+                    Value::String(String::from_utf8_lossy(&response.body).into_owned())
+                }),
+            );
+        }
+        self.record(
+            "raw",
+            &request.path,
+            Some(serde_json::json!({
+                "method": request.method,
+                "address": address.map(|address| address.to_string()),
+                "headers": request.headers,
+                "body": request.body.as_deref().map(String::from_utf8_lossy),
+            })),
+            result.as_ref().map(|response| {
+                (
+                    Some(response.status),
+                    serde_json::json!({
+                        "headers": response.headers,
+                        "body": String::from_utf8_lossy(&response.body),
+                    }),
+                )
+            }),
+        );
+    }
+}
+
+impl<T> ConformanceTransport for RecordingTransport<'_, T>
+where
+    T: ConformanceTransport,
+{
+    fn get_json(&self, path: &str) -> Result<Value, TransportError> {
+        let result = self.timed(|inner| inner.get_json(path));
+        if let Ok(payload) = &result {
+            let bytes = self.response_sizes(&[payload])[0];
+            self.meter_received(format!("GET {path}"), bytes, payload.clone());
+        }
+        self.record(
+            "get",
+            path,
+            None,
+            result.as_ref().map(|payload| (None, payload.clone())),
+        );
+        result
+    }
+
+    fn post_json(&self, path: &str, body: &Value) -> Result<(u16, Value), TransportError> {
+        self.meter_sent(body);
+        let result = self.timed(|inner| inner.post_json(path, body));
+        if let Ok((_, payload)) = &result {
+            self.note_collision(body, payload);
+            let bytes = self.response_sizes(&[payload])[0];
+            self.meter_received(format!("POST {path}"), bytes, payload.clone());
+        }
+        self.record(
+            "post",
+            path,
+            Some(body.clone()),
+            result
+                .as_ref()
+                .map(|(status, payload)| (Some(*status), payload.clone())),
+        );
+        result
+    }
+
+    fn websocket_first_response(&self, frame: &Value) -> Result<Value, TransportError> {
+        self.meter_sent(frame);
+        let result = self.timed(|inner| inner.websocket_first_response(frame));
+        if let Ok(payload) = &result {
+            self.note_collision(frame, payload);
+            let bytes = self.response_sizes(&[payload])[0];
+            self.meter_received(ws_label(frame), bytes, payload.clone());
+        }
+        self.record(
+            "ws",
+            "/ws",
+            Some(frame.clone()),
+            result.as_ref().map(|payload| (None, payload.clone())),
+        );
+        result
+    }
+
+    fn websocket_exchange(&self, frames: &[Value]) -> Result<Vec<Value>, TransportError> {
+        frames.iter().for_each(|frame| self.meter_sent(frame));
+        let result = self.timed(|inner| inner.websocket_exchange(frames));
+        if let Ok(responses) = &result {
+            let sizes = self.response_sizes(&responses.iter().collect::<Vec<_>>());
+            for ((frame, response), bytes) in frames.iter().zip(responses).zip(sizes) {
+                self.note_collision(frame, response);
+                self.meter_received(ws_label(frame), bytes, response.clone());
+            }
+        }
+        self.record(
+            "ws",
+            "/ws",
+            Some(Value::Array(frames.to_vec())),
+            result
+                .as_ref()
+                .map(|responses| (None, Value::Array(responses.clone()))),
+        );
+        result
+    }
+
+    fn request_raw(&self, request: &RawRequest) -> Result<RawResponse, TransportError> {
+        let result = self.timed(|inner| inner.request_raw(request));
+        self.record_raw(None, request, &result);
+        result
+    }
+
+    fn resolve_target(&self) -> Result<Vec<IpAddr>, TransportError> {
+        let result = self.timed(|inner| inner.resolve_target());
+        self.record(
+            "resolve",
+            "",
+            None,
+            result.as_ref().map(|addresses| {
+                (
+                    None,
+                    addresses
+                        .iter()
+                        .map(|address| Value::String(address.to_string()))
+                        .collect(),
+                )
+            }),
+        );
+        result
+    }
+
+    fn request_raw_via(
+        &self,
+        address: IpAddr,
+        request: &RawRequest,
+    ) -> Result<RawResponse, TransportError> {
+        let result = self.timed(|inner| inner.request_raw_via(address, request));
+        self.record_raw(Some(address), request, &result);
+        result
+    }
+
+    fn connection_setup_time(&self) -> Duration {
+        self.inner.connection_setup_time()
+    }
+}
+
+fn ws_label(frame: &Value) -> String {
+    let method = frame
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    format!("WS {method}")
+}
+
+fn json_len(value: &Value) -> u64 {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len() as u64)
+}
@@ -1,32 +1,476 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{Reconciliation, ScenarioTranscript};
+
+/// How a failing scenario affects the overall verdict.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Failure fails the run.
+    #[default]
+    Required,
+    /// Failure is reported but does not fail the run.
+    Recommended,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Required => "required",
+            Self::Recommended => "recommended",
+        }
+    }
+}
+
+/// Why a scenario was not executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The target does not advertise the capability the scenario exercises.
+    NotApplicable,
+    /// The scenario needs configuration that was not provided.
+    NotConfigured,
+    /// The scenario mutates gateway state and the run is read-only.
+    ReadOnly,
+    /// The scenario is listed in the config quarantine.
+    Quarantined,
+}
+
+impl SkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotApplicable => "not_applicable",
+            Self::NotConfigured => "not_configured",
+            Self::ReadOnly => "read_only",
+            Self::Quarantined => "quarantined",
+        }
+    }
+}
+
+/// Where a scenario's wall-clock time went, in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ScenarioTiming {
+    pub total_us: u64,
+    /// Time spent waiting on the transport, including `setup_us`.
+    pub transport_us: u64,
+    /// Time spent establishing connections.
+    pub setup_us: u64,
+}
+
+impl ScenarioTiming {
+    /// Time spent in the scenario itself, building requests and checking responses.
+    pub fn assertion_us(&self) -> u64 {
+        self.total_us.saturating_sub(self.transport_us)
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ConformanceOutcome {
     pub name: &'static str,
     pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<SkipReason>,
+    pub severity: Severity,
     pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Hash of the normalized responses; equal across runs with identical gateway behavior.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stability_hash: Option<String>,
+    pub timing: ScenarioTiming,
+    /// Error code that made the runner retry the scenario once with fresh identifiers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retried_after: Option<String>,
+    /// Remediation hints for implementers, attached to failing outcomes.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+impl ConformanceOutcome {
+    pub fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(name, true, detail)
+    }
+
+    pub fn fail(name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(name, false, detail)
+    }
+
+    pub fn skip(name: &'static str, reason: SkipReason, detail: impl Into<String>) -> Self {
+        let mut outcome = Self::new(name, false, detail);
+        outcome.skipped = Some(reason);
+        outcome
+    }
+
+    /// Whether the scenario ran and did not pass.
+    pub fn is_failure(&self) -> bool {
+        !self.passed && self.skipped.is_none()
+    }
+
+    pub fn status(&self) -> String {
+        match self.skipped {
+            Some(reason) => format!("SKIP ({})", reason.as_str()),
+            None if self.passed => "PASS".to_owned(),
+            None => "FAIL".to_owned(),
+        }
+    }
+
+    fn new(name: &'static str, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed,
+            skipped: None,
+            severity: Severity::Required,
+            detail: detail.into(),
+            owner: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            stability_hash: None,
+            timing: ScenarioTiming::default(),
+            retried_after: None,
+            notes: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ConformanceReport {
     pub total: usize,
     pub failed: usize,
+    pub skipped: usize,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Hash over every outcome's name and stability hash.
+    pub stability_hash: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
     pub outcomes: Vec<ConformanceOutcome>,
+    /// Comparison with the gateway's published self-assessment, when it publishes one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub self_assessment: Option<Reconciliation>,
+    #[serde(skip)]
+    pub transcripts: Vec<ScenarioTranscript>,
 }
 
 impl ConformanceReport {
     pub fn new(outcomes: Vec<ConformanceOutcome>) -> Self {
         let total = outcomes.len();
-        let failed = outcomes.iter().filter(|outcome| !outcome.passed).count();
+        let failed = outcomes
+            .iter()
+            .filter(|outcome| outcome.is_failure())
+            .count();
+        let skipped = outcomes
+            .iter()
+            .filter(|outcome| outcome.skipped.is_some())
+            .count();
+        let bytes_sent = outcomes.iter().map(|outcome| outcome.bytes_sent).sum();
+        let bytes_received = outcomes.iter().map(|outcome| outcome.bytes_received).sum();
+        let mut hasher = Sha256::new();
+        for outcome in &outcomes {
+            hasher.update(outcome.name);
+            hasher.update(outcome.stability_hash.as_deref().unwrap_or("-"));
+            hasher.update("\n");
+        }
+        let stability_hash = hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
 
         Self {
             total,
             failed,
+            skipped,
+            bytes_sent,
+            bytes_received,
+            stability_hash,
+            warnings: Vec::new(),
             outcomes,
+            self_assessment: None,
+            transcripts: Vec::new(),
         }
     }
 
+    /// Whether no `Required` scenario failed.
     pub fn is_passing(&self) -> bool {
-        self.failed == 0
+        self.outcomes
+            .iter()
+            .all(|outcome| !outcome.is_failure() || outcome.severity == Severity::Recommended)
+    }
+
+    pub fn passed(&self) -> usize {
+        self.total - self.failed - self.skipped
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut lines = vec![
+            "# Conformance Summary".to_owned(),
+            String::new(),
+            format!(
+                "{} scenarios, {} passed, {} failed, {} skipped",
+                self.total,
+                self.passed(),
+                self.failed,
+                self.skipped
+            ),
+            format!(
+                "payload bytes: {} sent, {} received",
+                self.bytes_sent, self.bytes_received
+            ),
+            format!("stability hash: `{}`", self.stability_hash),
+            String::new(),
+        ];
+        if !self.warnings.is_empty() {
+            lines.push("## Warnings".to_owned());
+            lines.push(String::new());
+            lines.extend(
+                self.warnings
+                    .iter()
+                    .map(|warning| format!("- {}", markdown_cell(warning))),
+            );
+            lines.push(String::new());
+        }
+        lines.push("| Status | Scenario | Severity | Owner | Detail |".to_owned());
+        lines.push("| --- | --- | --- | --- | --- |".to_owned());
+        for outcome in &self.outcomes {
+            lines.push(format!(
+                "| {} | `{}` | {} | {} | {} |",
+                outcome.status(),
+                outcome.name,
+                outcome.severity.as_str(),
+                outcome.owner.as_deref().unwrap_or(""),
+                markdown_cell(&outcome.detail)
+            ));
+        }
+
+        let mut failures_by_owner = BTreeMap::<&str, Vec<&str>>::new();
+        for outcome in self.outcomes.iter().filter(|outcome| outcome.is_failure()) {
+            failures_by_owner
+                .entry(outcome.owner.as_deref().unwrap_or("unowned"))
+                .or_default()
+                .push(outcome.name);
+        }
+        if !failures_by_owner.is_empty() {
+            lines.push(String::new());
+            lines.push("## Failures by Owner".to_owned());
+            for (owner, names) in failures_by_owner {
+                lines.push(String::new());
+                lines.push(format!("### {owner}"));
+                lines.push(String::new());
+                lines.extend(names.iter().map(|name| format!("- `{name}`")));
+            }
+        }
+
+        let remediation = self
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.is_failure() && !outcome.notes.is_empty())
+            .collect::<Vec<_>>();
+        if !remediation.is_empty() {
+            lines.push(String::new());
+            lines.push("## Remediation".to_owned());
+            for outcome in remediation {
+                lines.push(String::new());
+                lines.push(format!("### `{}`", outcome.name));
+                lines.push(String::new());
+                lines.extend(
+                    outcome
+                        .notes
+                        .iter()
+                        .map(|note| format!("- {}", markdown_cell(note))),
+                );
+            }
+        }
+
+        let mut skipped_by_reason = BTreeMap::<SkipReason, Vec<&str>>::new();
+        for outcome in &self.outcomes {
+            if let Some(reason) = outcome.skipped {
+                skipped_by_reason
+                    .entry(reason)
+                    .or_default()
+                    .push(outcome.name);
+            }
+        }
+        if !skipped_by_reason.is_empty() {
+            lines.push(String::new());
+            lines.push("## Skipped by Reason".to_owned());
+            for (reason, names) in skipped_by_reason {
+                lines.push(String::new());
+                lines.push(format!("### {}", reason.as_str()));
+                lines.push(String::new());
+                lines.extend(names.iter().map(|name| format!("- `{name}`")));
+            }
+        }
+
+        if let Some(reconciliation) = &self.self_assessment {
+            lines.push(String::new());
+            lines.push("## Self-Assessment".to_owned());
+            if reconciliation.is_consistent() {
+                lines.push(String::new());
+                lines.push("Published claims match the observed results.".to_owned());
+            }
+            for (heading, names) in reconciliation.sections() {
+                if names.is_empty() {
+                    continue;
+                }
+                lines.push(String::new());
+                lines.push(format!("### {heading}"));
+                lines.push(String::new());
+                lines.extend(names.iter().map(|name| format!("- `{name}`")));
+            }
+        }
+
+        lines.push(String::new());
+        lines.join("\n")
     }
+
+    /// Outcomes sorted by total duration, slowest first, with a totals row.
+    pub fn profile_table(&self) -> String {
+        let mut outcomes = self.outcomes.iter().collect::<Vec<_>>();
+        outcomes.sort_by_key(|outcome| Reverse(outcome.timing.total_us));
+
+        let width = outcomes
+            .iter()
+            .map(|outcome| outcome.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Scenario".len());
+        let row = |name: &str, timing: &ScenarioTiming| {
+            format!(
+                "{name:<width$}  {:>10}  {:>10}  {:>10}  {:>10}",
+                millis(timing.total_us),
+                millis(timing.transport_us),
+                millis(timing.assertion_us()),
+                millis(timing.setup_us)
+            )
+        };
+
+        let mut lines = vec![format!(
+            "{:<width$}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Scenario", "total ms", "transport", "assertion", "setup"
+        )];
+        let mut totals = ScenarioTiming::default();
+        for outcome in outcomes {
+            lines.push(row(outcome.name, &outcome.timing));
+            totals.total_us += outcome.timing.total_us;
+            totals.transport_us += outcome.timing.transport_us;
+            totals.setup_us += outcome.timing.setup_us;
+        }
+        lines.push(row("total", &totals));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    pub fn to_html(&self) -> String {
+        let warnings = if self.warnings.is_empty() {
+            String::new()
+        } else {
+            let items = self
+                .warnings
+                .iter()
+                .map(|warning| format!("<li>{}</li>", html_escape(warning)))
+                .collect::<String>();
+            format!("<h2>Warnings</h2>\n<ul>{items}</ul>\n")
+        };
+        let self_assessment = match &self.self_assessment {
+            None => String::new(),
+            Some(reconciliation) if reconciliation.is_consistent() => {
+                "<h2>Self-Assessment</h2>\n<p>Published claims match the observed results.</p>\n"
+                    .to_owned()
+            }
+            Some(reconciliation) => {
+                let mut section = "<h2>Self-Assessment</h2>\n".to_owned();
+                for (heading, names) in reconciliation.sections() {
+                    if names.is_empty() {
+                        continue;
+                    }
+                    let items = names
+                        .iter()
+                        .map(|name| format!("<li><code>{}</code></li>", html_escape(name)))
+                        .collect::<String>();
+                    section.push_str(&format!("<h3>{heading}</h3>\n<ul>{items}</ul>\n"));
+                }
+                section
+            }
+        };
+        let mut rows = String::new();
+        for outcome in &self.outcomes {
+            let class = match outcome.skipped {
+                Some(_) => "skip",
+                None if outcome.passed => "pass",
+                None => "fail",
+            };
+            rows.push_str(&format!(
+                "<tr class=\"{class}\"><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                outcome.status(),
+                html_escape(outcome.name),
+                outcome.severity.as_str(),
+                html_escape(outcome.owner.as_deref().unwrap_or("")),
+                html_escape(&outcome.detail)
+            ));
+            if outcome.is_failure() && !outcome.notes.is_empty() {
+                let notes = outcome
+                    .notes
+                    .iter()
+                    .map(|note| format!("<li>{}</li>", html_escape(note)))
+                    .collect::<String>();
+                rows.push_str(&format!(
+                    "<tr class=\"notes\"><td></td><td colspan=\"4\"><ul>{notes}</ul></td></tr>\n"
+                ));
+            }
+        }
+
+        format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Conformance Report</title>
+<style>
+body {{ font-family: sans-serif; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+tr.pass td:first-child {{ color: #1a7f37; }}
+tr.fail td:first-child {{ color: #cf222e; }}
+tr.skip td:first-child {{ color: #6e7781; }}
+</style>
+</head>
+<body>
+<h1>Conformance Report</h1>
+<p>{} scenarios, {} passed, {} failed, {} skipped</p>
+<p>payload bytes: {} sent, {} received</p>
+<p>stability hash: <code>{}</code></p>
+{warnings}<table>
+<tr><th>Status</th><th>Scenario</th><th>Severity</th><th>Owner</th><th>Detail</th></tr>
+{rows}</table>
+{self_assessment}</body>
+</html>
+",
+            self.total,
+            self.passed(),
+            self.failed,
+            self.skipped,
+            self.bytes_sent,
+            self.bytes_received,
+            self.stability_hash
+        )
+    }
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn millis(micros: u64) -> String {
+    format!("{:.1}", micros as f64 / 1000.0)
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
 }
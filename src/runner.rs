@@ -1,10 +1,24 @@
-use crate::{scenario::Scenario, ConformanceReport, ConformanceTransport};
+use std::time::{Duration, Instant};
+
+use crate::{
+    assessment::{SelfAssessment, SELF_ASSESSMENT_PATH},
+    context::RunContext,
+    hints,
+    recorder::{Recording, RecordingTransport, ScenarioTranscript},
+    scenario::{Scenario, ScenarioDescriptor},
+    ConformanceConfig, ConformanceOutcome, ConformanceReport, ConformanceTransport, ScenarioTiming,
+    SkipReason, TransportError,
+};
 
 pub struct ConformanceRunner<T>
 where
     T: ConformanceTransport,
 {
     transport: T,
+    config: ConformanceConfig,
+    capture_transcripts: bool,
+    read_only: bool,
+    selection: Option<Vec<String>>,
 }
 
 impl<T> ConformanceRunner<T>
@@ -12,15 +26,235 @@ where
     T: ConformanceTransport,
 {
     pub fn new(transport: T) -> Self {
-        Self { transport }
+        Self {
+            transport,
+            config: ConformanceConfig::default(),
+            capture_transcripts: false,
+            read_only: false,
+            selection: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: ConformanceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_transcripts(mut self, capture: bool) -> Self {
+        self.capture_transcripts = capture;
+        self
+    }
+
+    /// Lowers `limits.max_capture_bytes` so every scenario keeps at most `bytes` of payloads.
+    pub fn with_max_capture_bytes(mut self, bytes: u64) -> Self {
+        let limits = &mut self.config.limits;
+        limits.max_capture_bytes = limits.max_capture_bytes.min(bytes);
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Limits `run` to the named scenarios, keeping suite order.
+    pub fn with_selection(mut self, names: Vec<String>) -> Self {
+        self.selection = Some(names);
+        self
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn scenario_names() -> Vec<&'static str> {
+        Scenario::all().iter().map(Scenario::name).collect()
+    }
+
+    pub fn catalogue() -> Vec<ScenarioDescriptor> {
+        Scenario::catalogue()
     }
 
     pub fn run(&self) -> ConformanceReport {
-        let outcomes = Scenario::all()
+        let ctx = RunContext::new(&self.config);
+        let mut outcomes = Vec::new();
+        let mut transcripts = Vec::new();
+        let mut warnings = Vec::new();
+        for scenario in Scenario::all() {
+            if self
+                .selection
+                .as_ref()
+                .is_some_and(|names| !names.iter().any(|name| name == scenario.name()))
+            {
+                continue;
+            }
+            let (outcome, recording) = self.run_one(&scenario, &ctx);
+            warnings.extend(self.oversized_responses(&outcome, &recording));
+            ctx.record_echoes(outcome.name, &recording.received);
+            if self.capture_transcripts {
+                transcripts.push(ScenarioTranscript {
+                    scenario: outcome.name,
+                    entries: recording.entries,
+                });
+            }
+            outcomes.push(outcome);
+        }
+
+        let mut report = ConformanceReport::new(outcomes);
+        report.warnings = warnings;
+        report.transcripts = transcripts;
+        match self.self_assessment() {
+            Ok(Some(assessment)) => {
+                report.self_assessment = Some(assessment.reconcile(&report.outcomes));
+            }
+            Ok(None) => {}
+            Err(warning) => report.warnings.push(warning),
+        }
+        report
+    }
+
+    /// The gateway's published self-assessment; `Ok(None)` when the path is a 404.
+    fn self_assessment(&self) -> Result<Option<SelfAssessment>, String> {
+        let manifest = match self.transport.get_json(SELF_ASSESSMENT_PATH) {
+            Ok(manifest) => manifest,
+            Err(TransportError::Status { status: 404, .. }) => return Ok(None),
+            Err(error) => {
+                return Err(format!(
+                    "self-assessment at {SELF_ASSESSMENT_PATH} could not be fetched: {error}"
+                ));
+            }
+        };
+        serde_json::from_value(manifest).map(Some).map_err(|error| {
+            format!("self-assessment at {SELF_ASSESSMENT_PATH} is invalid: {error}")
+        })
+    }
+
+    pub fn run_scenario(&self, name: &str) -> Option<ConformanceOutcome> {
+        let ctx = RunContext::new(&self.config);
+        Scenario::from_name(name).map(|scenario| self.run_one(&scenario, &ctx).0)
+    }
+
+    fn run_one(&self, scenario: &Scenario, ctx: &RunContext) -> (ConformanceOutcome, Recording) {
+        if let Some(mut outcome) = self.skip(scenario) {
+            outcome.severity = scenario.severity();
+            return (outcome, Recording::default());
+        }
+
+        ctx.enter(scenario.name());
+        let (mut outcome, mut recording, mut total) = self.attempt(scenario, ctx);
+        if let Some(code) = identifier_collision(&outcome, &recording, ctx) {
+            ctx.refresh_nonce();
+            let (retry, mut retry_recording, retry_total) = self.attempt(scenario, ctx);
+            retry_recording.absorb(recording);
+            outcome = retry;
+            outcome.detail = format!(
+                "{} (retried with fresh identifiers after {code})",
+                outcome.detail
+            );
+            outcome.retried_after = Some(code);
+            recording = retry_recording;
+            total += retry_total;
+        }
+
+        outcome.severity = scenario.severity();
+        outcome.bytes_sent = recording.bytes_sent;
+        outcome.bytes_received = recording.bytes_received;
+        outcome.timing = ScenarioTiming {
+            total_us: micros(total),
+            transport_us: micros(recording.transport_time),
+            setup_us: micros(recording.setup_time),
+        };
+        if outcome.is_failure() {
+            outcome.owner = self.config.owner_for(scenario.tags()).map(str::to_owned);
+            outcome.notes = hints::remediation_notes(outcome.name);
+        }
+        (outcome, recording)
+    }
+
+    fn attempt(
+        &self,
+        scenario: &Scenario,
+        ctx: &RunContext,
+    ) -> (ConformanceOutcome, Recording, Duration) {
+        let recorder = RecordingTransport::new(
+            &self.transport,
+            self.capture_transcripts,
+            self.config.limits.max_capture_bytes,
+        );
+        let started = Instant::now();
+        let mut outcome = scenario.run(&recorder, ctx);
+        let total = started.elapsed();
+        let recording = recorder.finish();
+        outcome.stability_hash = Some(recording.stability_hash(&ctx.nonce()));
+        (outcome, recording, total)
+    }
+
+    fn skip(&self, scenario: &Scenario) -> Option<ConformanceOutcome> {
+        let name = scenario.name();
+        if self.config.is_quarantined(name) {
+            return Some(ConformanceOutcome::skip(
+                name,
+                SkipReason::Quarantined,
+                "scenario is quarantined by config",
+            ));
+        }
+        if let Some(profile) = scenario.profile() {
+            if !self.config.has_profile(profile) {
+                return Some(ConformanceOutcome::skip(
+                    name,
+                    SkipReason::NotConfigured,
+                    format!("scenario needs the {profile:?} profile in config profiles"),
+                ));
+            }
+        }
+        if self.read_only && scenario.mutates_state() {
+            return Some(ConformanceOutcome::skip(
+                name,
+                SkipReason::ReadOnly,
+                "scenario mutates gateway state and the run is read-only",
+            ));
+        }
+        None
+    }
+
+    fn oversized_responses(
+        &self,
+        outcome: &ConformanceOutcome,
+        recording: &Recording,
+    ) -> Vec<String> {
+        let limit = self.config.limits.max_response_bytes;
+        recording
+            .responses
             .iter()
-            .map(|scenario| scenario.run(&self.transport))
-            .collect();
+            .filter(|response| response.bytes > limit)
+            .map(|response| {
+                format!(
+                    "{}: {} response was {} bytes, exceeding the {limit} byte limit",
+                    outcome.name, response.label, response.bytes
+                )
+            })
+            .collect()
+    }
+}
 
-        ConformanceReport::new(outcomes)
+/// `DUPLICATE` or `CONFLICT` error code behind a failure, returned only when the rejected
+/// request carried an identifier the scenario generated, which usually means it collided with
+/// one from another run rather than a protocol violation.
+fn identifier_collision(
+    outcome: &ConformanceOutcome,
+    recording: &Recording,
+    ctx: &RunContext,
+) -> Option<String> {
+    if !outcome.is_failure() {
+        return None;
     }
+    recording
+        .collisions
+        .iter()
+        .find(|(_, params)| ctx.carries_own_id(outcome.name, params))
+        .map(|(code, _)| code.clone())
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
 }
@@ -1,12 +1,25 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
 use serde_json::Value;
 
-use crate::{ConformanceOutcome, ConformanceTransport, EXPECTED_PROTOCOL_VERSION};
+use crate::{
+    context::RunContext, ConformanceOutcome, ConformanceTransport, Matcher, MessageExpectation,
+    RawRequest, Severity, SkipReason,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Scenario {
     HealthzOkTrue,
+    HealthzHeadNoBody,
+    HealthzDualStackReachable,
+    HealthzDateWithinClockSkew,
     ReadyzOkTrue,
     InfoProtocolVersion,
     InfoMethodsIncludeHealthAndStatus,
@@ -14,9 +27,15 @@ pub enum Scenario {
     ToolsInvokeGatewayRequest,
     ToolsInvokeGatewayRequestActionFallback,
     ToolsInvokeRejectsUnknownTool,
+    ToolsInvokeRejectsTextPlainContentType,
+    ToolsInvokeRejectsMissingContentType,
+    ToolsInvokeAcceptsJsonCharsetContentType,
     WsHandshakeRequiresConnectFirstFrame,
+    WsFeatureDisabledMethodsRejected,
+    WsFeatureEnabledMethodsAdvertised,
     WsChannelsStatusIncludesAccountViews,
     WsChannelsLogoutAccountPersists,
+    WsChannelsLogoutReadYourWrites,
     WsAgentDeferredWaitCompletes,
     WsChatSendDeferredWaitCompletes,
     WsChatAbortCancelsDeferredRun,
@@ -24,15 +43,38 @@ pub enum Scenario {
     WsChatAbortSessionWideCancelsDeferredChatSendRuns,
     WsChatAbortSessionWideCancelsRuns,
     WsAgentWaitTimeoutForMissingRun,
+    WsAgentWaitRejectsMissingRunId,
+    WsChatSendRejectsMissingMessage,
+    WsChatSendRejectsEmptyMessage,
+    WsChatSendRejectsNullMessage,
     WsChatAbortRejectsRunSessionMismatch,
     WsChatAbortCompletedRunNoop,
     WsChatAbortUnknownRunNoop,
+    RestartRecoversHealthAndRuns,
+    CompatChannelsStatusLegacyChannelsArray,
+    CompatChannelsStatusLegacyChannelLabels,
+    RunIdentifierIsolation,
+}
+
+/// Profile gating the deprecated v2 field scenarios.
+const V2_COMPAT_PROFILE: &str = "v2-compat";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioDescriptor {
+    pub name: &'static str,
+    pub tags: &'static [&'static str],
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<&'static str>,
 }
 
 impl Scenario {
-    pub fn all() -> [Self; 21] {
+    pub fn all() -> [Self; 38] {
         [
             Self::HealthzOkTrue,
+            Self::HealthzHeadNoBody,
+            Self::HealthzDualStackReachable,
+            Self::HealthzDateWithinClockSkew,
             Self::ReadyzOkTrue,
             Self::InfoProtocolVersion,
             Self::InfoMethodsIncludeHealthAndStatus,
@@ -40,9 +82,15 @@ impl Scenario {
             Self::ToolsInvokeGatewayRequest,
             Self::ToolsInvokeGatewayRequestActionFallback,
             Self::ToolsInvokeRejectsUnknownTool,
+            Self::ToolsInvokeRejectsTextPlainContentType,
+            Self::ToolsInvokeRejectsMissingContentType,
+            Self::ToolsInvokeAcceptsJsonCharsetContentType,
             Self::WsHandshakeRequiresConnectFirstFrame,
+            Self::WsFeatureDisabledMethodsRejected,
+            Self::WsFeatureEnabledMethodsAdvertised,
             Self::WsChannelsStatusIncludesAccountViews,
             Self::WsChannelsLogoutAccountPersists,
+            Self::WsChannelsLogoutReadYourWrites,
             Self::WsAgentDeferredWaitCompletes,
             Self::WsChatSendDeferredWaitCompletes,
             Self::WsChatAbortCancelsDeferredRun,
@@ -50,17 +98,202 @@ impl Scenario {
             Self::WsChatAbortSessionWideCancelsDeferredChatSendRuns,
             Self::WsChatAbortSessionWideCancelsRuns,
             Self::WsAgentWaitTimeoutForMissingRun,
+            Self::WsAgentWaitRejectsMissingRunId,
+            Self::WsChatSendRejectsMissingMessage,
+            Self::WsChatSendRejectsEmptyMessage,
+            Self::WsChatSendRejectsNullMessage,
             Self::WsChatAbortRejectsRunSessionMismatch,
             Self::WsChatAbortCompletedRunNoop,
             Self::WsChatAbortUnknownRunNoop,
+            Self::RestartRecoversHealthAndRuns,
+            Self::CompatChannelsStatusLegacyChannelsArray,
+            Self::CompatChannelsStatusLegacyChannelLabels,
+            Self::RunIdentifierIsolation,
         ]
     }
 
-    pub fn run<T: ConformanceTransport>(&self, transport: &T) -> ConformanceOutcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HealthzOkTrue => "healthz.ok_true",
+            Self::HealthzHeadNoBody => "healthz.head_no_body",
+            Self::HealthzDualStackReachable => "healthz.dual_stack_reachable",
+            Self::HealthzDateWithinClockSkew => "healthz.date_within_clock_skew",
+            Self::ReadyzOkTrue => "readyz.ok_true",
+            Self::InfoProtocolVersion => "info.protocol_version",
+            Self::InfoMethodsIncludeHealthAndStatus => "info.methods_include_health_status",
+            Self::UnknownChannelWebhookNotFound => "channels.unknown_webhook_not_found",
+            Self::ToolsInvokeGatewayRequest => "tools.invoke_gateway_request",
+            Self::ToolsInvokeGatewayRequestActionFallback => {
+                "tools.invoke_gateway_request_action_fallback"
+            }
+            Self::ToolsInvokeRejectsUnknownTool => "tools.invoke_rejects_unknown_tool",
+            Self::ToolsInvokeRejectsTextPlainContentType => {
+                "tools.invoke_rejects_text_plain_content_type"
+            }
+            Self::ToolsInvokeRejectsMissingContentType => {
+                "tools.invoke_rejects_missing_content_type"
+            }
+            Self::ToolsInvokeAcceptsJsonCharsetContentType => {
+                "tools.invoke_accepts_json_charset_content_type"
+            }
+            Self::WsHandshakeRequiresConnectFirstFrame => {
+                "ws.handshake_requires_connect_first_frame"
+            }
+            Self::WsFeatureDisabledMethodsRejected => "ws.feature_disabled_methods_rejected",
+            Self::WsFeatureEnabledMethodsAdvertised => "ws.feature_enabled_methods_advertised",
+            Self::WsChannelsStatusIncludesAccountViews => {
+                "ws.channels_status_includes_account_views"
+            }
+            Self::WsChannelsLogoutAccountPersists => "ws.channels_logout_account_persists",
+            Self::WsChannelsLogoutReadYourWrites => "ws.channels_logout_read_your_writes",
+            Self::WsAgentDeferredWaitCompletes => "ws.agent_deferred_wait_completes",
+            Self::WsChatSendDeferredWaitCompletes => "ws.chat_send_deferred_wait_completes",
+            Self::WsChatAbortCancelsDeferredRun => "ws.chat_abort_cancels_deferred_run",
+            Self::WsChatAbortCancelsDeferredChatSendRun => {
+                "ws.chat_abort_cancels_deferred_chat_send_run"
+            }
+            Self::WsChatAbortSessionWideCancelsDeferredChatSendRuns => {
+                "ws.chat_abort_session_wide_cancels_deferred_chat_send_runs"
+            }
+            Self::WsChatAbortSessionWideCancelsRuns => "ws.chat_abort_session_wide_cancels_runs",
+            Self::WsAgentWaitTimeoutForMissingRun => "ws.agent_wait_timeout_for_missing_run",
+            Self::WsAgentWaitRejectsMissingRunId => "ws.agent_wait_rejects_missing_run_id",
+            Self::WsChatSendRejectsMissingMessage => "ws.chat_send_rejects_missing_message",
+            Self::WsChatSendRejectsEmptyMessage => "ws.chat_send_rejects_empty_message",
+            Self::WsChatSendRejectsNullMessage => "ws.chat_send_rejects_null_message",
+            Self::WsChatAbortRejectsRunSessionMismatch => {
+                "ws.chat_abort_rejects_run_session_mismatch"
+            }
+            Self::WsChatAbortCompletedRunNoop => "ws.chat_abort_completed_run_noop",
+            Self::WsChatAbortUnknownRunNoop => "ws.chat_abort_unknown_run_noop",
+            Self::RestartRecoversHealthAndRuns => "restart.recovers_health_and_runs",
+            Self::CompatChannelsStatusLegacyChannelsArray => {
+                "compat.channels_status_legacy_channels_array"
+            }
+            Self::CompatChannelsStatusLegacyChannelLabels => {
+                "compat.channels_status_legacy_channel_labels"
+            }
+            Self::RunIdentifierIsolation => "run.identifier_isolation",
+        }
+    }
+
+    /// Tags are ordered most specific first.
+    pub fn tags(&self) -> &'static [&'static str] {
+        match self {
+            Self::HealthzOkTrue
+            | Self::HealthzHeadNoBody
+            | Self::HealthzDualStackReachable
+            | Self::HealthzDateWithinClockSkew
+            | Self::ReadyzOkTrue => &["health", "http"],
+            Self::InfoProtocolVersion | Self::InfoMethodsIncludeHealthAndStatus => {
+                &["info", "http"]
+            }
+            Self::UnknownChannelWebhookNotFound => &["channels", "http"],
+            Self::ToolsInvokeGatewayRequest
+            | Self::ToolsInvokeGatewayRequestActionFallback
+            | Self::ToolsInvokeRejectsUnknownTool
+            | Self::ToolsInvokeRejectsTextPlainContentType
+            | Self::ToolsInvokeRejectsMissingContentType
+            | Self::ToolsInvokeAcceptsJsonCharsetContentType => &["tools", "http"],
+            Self::WsHandshakeRequiresConnectFirstFrame => &["handshake", "ws"],
+            Self::WsFeatureDisabledMethodsRejected => &["features", "ws"],
+            Self::WsFeatureEnabledMethodsAdvertised => &["features", "ws", "http"],
+            Self::WsChannelsStatusIncludesAccountViews
+            | Self::WsChannelsLogoutAccountPersists
+            | Self::WsChannelsLogoutReadYourWrites => &["channels", "ws"],
+            Self::WsAgentDeferredWaitCompletes
+            | Self::WsAgentWaitTimeoutForMissingRun
+            | Self::WsAgentWaitRejectsMissingRunId => &["agent", "ws"],
+            Self::WsChatSendDeferredWaitCompletes
+            | Self::WsChatSendRejectsMissingMessage
+            | Self::WsChatSendRejectsEmptyMessage
+            | Self::WsChatSendRejectsNullMessage
+            | Self::WsChatAbortCancelsDeferredRun
+            | Self::WsChatAbortCancelsDeferredChatSendRun
+            | Self::WsChatAbortSessionWideCancelsDeferredChatSendRuns
+            | Self::WsChatAbortSessionWideCancelsRuns
+            | Self::WsChatAbortRejectsRunSessionMismatch
+            | Self::WsChatAbortCompletedRunNoop
+            | Self::WsChatAbortUnknownRunNoop => &["chat", "ws"],
+            Self::RestartRecoversHealthAndRuns => &["restart", "http", "ws"],
+            Self::CompatChannelsStatusLegacyChannelsArray
+            | Self::CompatChannelsStatusLegacyChannelLabels => &["compat", "channels", "ws"],
+            Self::RunIdentifierIsolation => &["run", "http", "ws"],
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::HealthzHeadNoBody | Self::HealthzDateWithinClockSkew => Severity::Recommended,
+            _ => Severity::Required,
+        }
+    }
+
+    /// Whether the scenario starts runs, sends messages, or logs out accounts.
+    pub fn mutates_state(&self) -> bool {
+        matches!(
+            self,
+            Self::WsFeatureDisabledMethodsRejected
+                | Self::WsFeatureEnabledMethodsAdvertised
+                | Self::WsChannelsLogoutAccountPersists
+                | Self::WsChannelsLogoutReadYourWrites
+                | Self::WsAgentDeferredWaitCompletes
+                | Self::WsChatSendDeferredWaitCompletes
+                | Self::WsChatSendRejectsMissingMessage
+                | Self::WsChatSendRejectsEmptyMessage
+                | Self::WsChatSendRejectsNullMessage
+                | Self::WsChatAbortCancelsDeferredRun
+                | Self::WsChatAbortCancelsDeferredChatSendRun
+                | Self::WsChatAbortSessionWideCancelsDeferredChatSendRuns
+                | Self::WsChatAbortSessionWideCancelsRuns
+                | Self::WsChatAbortRejectsRunSessionMismatch
+                | Self::WsChatAbortCompletedRunNoop
+                | Self::WsChatAbortUnknownRunNoop
+                | Self::RestartRecoversHealthAndRuns
+        )
+    }
+
+    /// Gateway profile the target must claim in config for the scenario to run.
+    pub fn profile(&self) -> Option<&'static str> {
+        match self {
+            Self::CompatChannelsStatusLegacyChannelsArray
+            | Self::CompatChannelsStatusLegacyChannelLabels => Some(V2_COMPAT_PROFILE),
+            _ => None,
+        }
+    }
+
+    /// Descriptors for every scenario, in suite order.
+    pub fn catalogue() -> Vec<ScenarioDescriptor> {
+        Self::all().iter().map(Self::descriptor).collect()
+    }
+
+    pub fn descriptor(&self) -> ScenarioDescriptor {
+        ScenarioDescriptor {
+            name: self.name(),
+            tags: self.tags(),
+            severity: self.severity(),
+            profile: self.profile(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|scenario| scenario.name() == name)
+    }
+
+    pub(crate) fn run<T: ConformanceTransport>(
+        &self,
+        transport: &T,
+        ctx: &RunContext,
+    ) -> ConformanceOutcome {
         match self {
             Self::HealthzOkTrue => run_healthz(transport),
+            Self::HealthzHeadNoBody => run_healthz_head(transport),
+            Self::HealthzDualStackReachable => run_healthz_dual_stack(transport, ctx),
+            Self::HealthzDateWithinClockSkew => run_healthz_date_within_clock_skew(transport, ctx),
             Self::ReadyzOkTrue => run_readyz(transport),
-            Self::InfoProtocolVersion => run_info_protocol_version(transport),
+            Self::InfoProtocolVersion => run_info_protocol_version(transport, ctx),
             Self::InfoMethodsIncludeHealthAndStatus => {
                 run_info_methods_include_health_and_status(transport)
             }
@@ -70,39 +303,98 @@ impl Scenario {
                 run_tools_invoke_gateway_request_action_fallback(transport)
             }
             Self::ToolsInvokeRejectsUnknownTool => run_tools_invoke_rejects_unknown_tool(transport),
+            Self::ToolsInvokeRejectsTextPlainContentType => {
+                run_tools_invoke_rejects_text_plain_content_type(transport)
+            }
+            Self::ToolsInvokeRejectsMissingContentType => {
+                run_tools_invoke_rejects_missing_content_type(transport)
+            }
+            Self::ToolsInvokeAcceptsJsonCharsetContentType => {
+                run_tools_invoke_accepts_json_charset_content_type(transport)
+            }
             Self::WsHandshakeRequiresConnectFirstFrame => {
                 run_ws_handshake_requires_connect_first_frame(transport)
             }
+            Self::WsFeatureDisabledMethodsRejected => {
+                run_ws_feature_disabled_methods_rejected(transport, ctx)
+            }
+            Self::WsFeatureEnabledMethodsAdvertised => {
+                run_ws_feature_enabled_methods_advertised(transport, ctx)
+            }
             Self::WsChannelsStatusIncludesAccountViews => {
-                run_ws_channels_status_includes_account_views(transport)
+                run_ws_channels_status_includes_account_views(transport, ctx)
             }
             Self::WsChannelsLogoutAccountPersists => {
-                run_ws_channels_logout_account_persists(transport)
+                run_ws_channels_logout_account_persists(transport, ctx)
+            }
+            Self::WsChannelsLogoutReadYourWrites => {
+                run_ws_channels_logout_read_your_writes(transport, ctx)
+            }
+            Self::WsAgentDeferredWaitCompletes => {
+                run_ws_agent_deferred_wait_completes(transport, ctx)
             }
-            Self::WsAgentDeferredWaitCompletes => run_ws_agent_deferred_wait_completes(transport),
             Self::WsChatSendDeferredWaitCompletes => {
-                run_ws_chat_send_deferred_wait_completes(transport)
+                run_ws_chat_send_deferred_wait_completes(transport, ctx)
             }
             Self::WsChatAbortCancelsDeferredRun => {
-                run_ws_chat_abort_cancels_deferred_run(transport)
+                run_ws_chat_abort_cancels_deferred_run(transport, ctx)
             }
             Self::WsChatAbortCancelsDeferredChatSendRun => {
-                run_ws_chat_abort_cancels_deferred_chat_send_run(transport)
+                run_ws_chat_abort_cancels_deferred_chat_send_run(transport, ctx)
             }
             Self::WsChatAbortSessionWideCancelsDeferredChatSendRuns => {
-                run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs(transport)
+                run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs(transport, ctx)
             }
             Self::WsChatAbortSessionWideCancelsRuns => {
-                run_ws_chat_abort_session_wide_cancels_runs(transport)
+                run_ws_chat_abort_session_wide_cancels_runs(transport, ctx)
             }
             Self::WsAgentWaitTimeoutForMissingRun => {
-                run_ws_agent_wait_timeout_for_missing_run(transport)
+                run_ws_agent_wait_timeout_for_missing_run(transport, ctx)
+            }
+            Self::WsAgentWaitRejectsMissingRunId => {
+                run_ws_agent_wait_rejects_missing_run_id(transport, ctx)
+            }
+            Self::WsChatSendRejectsMissingMessage => {
+                let expected = ctx.scenario().chat_send.missing;
+                run_ws_chat_send_rejects_message(transport, ctx, self.name(), None, expected)
+            }
+            Self::WsChatSendRejectsEmptyMessage => {
+                let expected = ctx.scenario().chat_send.empty;
+                run_ws_chat_send_rejects_message(
+                    transport,
+                    ctx,
+                    self.name(),
+                    Some(Value::from("")),
+                    expected,
+                )
+            }
+            Self::WsChatSendRejectsNullMessage => {
+                let expected = ctx.scenario().chat_send.null;
+                run_ws_chat_send_rejects_message(
+                    transport,
+                    ctx,
+                    self.name(),
+                    Some(Value::Null),
+                    expected,
+                )
             }
             Self::WsChatAbortRejectsRunSessionMismatch => {
-                run_ws_chat_abort_rejects_run_session_mismatch(transport)
+                run_ws_chat_abort_rejects_run_session_mismatch(transport, ctx)
+            }
+            Self::WsChatAbortCompletedRunNoop => {
+                run_ws_chat_abort_completed_run_noop(transport, ctx)
+            }
+            Self::WsChatAbortUnknownRunNoop => run_ws_chat_abort_unknown_run_noop(transport, ctx),
+            Self::RestartRecoversHealthAndRuns => {
+                run_restart_recovers_health_and_runs(transport, ctx)
+            }
+            Self::CompatChannelsStatusLegacyChannelsArray => {
+                run_compat_channels_status_legacy_channels_array(transport, ctx)
+            }
+            Self::CompatChannelsStatusLegacyChannelLabels => {
+                run_compat_channels_status_legacy_channel_labels(transport, ctx)
             }
-            Self::WsChatAbortCompletedRunNoop => run_ws_chat_abort_completed_run_noop(transport),
-            Self::WsChatAbortUnknownRunNoop => run_ws_chat_abort_unknown_run_noop(transport),
+            Self::RunIdentifierIsolation => run_identifier_isolation(ctx),
         }
     }
 }
@@ -114,24 +406,173 @@ fn run_healthz<T: ConformanceTransport>(transport: &T) -> ConformanceOutcome {
         Ok(payload) => {
             let ok = payload.get("ok").and_then(Value::as_bool).unwrap_or(false);
             if ok {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "health endpoint returned ok=true".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "health endpoint returned ok=true")
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: "health endpoint did not return {\"ok\":true}".to_owned(),
+                ConformanceOutcome::fail(name, "health endpoint did not return {\"ok\":true}")
+            }
+        }
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("health endpoint request failed: {error}"))
+        }
+    }
+}
+
+fn run_healthz_head<T: ConformanceTransport>(transport: &T) -> ConformanceOutcome {
+    let name = "healthz.head_no_body";
+
+    match transport.request_raw(&RawRequest::new("HEAD", "/healthz")) {
+        Ok(response) if response.status == 200 && response.body.is_empty() => {
+            ConformanceOutcome::pass(
+                name,
+                "health endpoint answered HEAD with status=200 and no body",
+            )
+        }
+        Ok(response) => ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected status=200 with empty body for HEAD, found status={}, body bytes={}",
+                response.status,
+                response.body.len()
+            ),
+        ),
+        Err(error) => ConformanceOutcome::fail(
+            name,
+            format!("health endpoint HEAD request failed: {error}"),
+        ),
+    }
+}
+
+fn run_healthz_dual_stack<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "healthz.dual_stack_reachable";
+    if !ctx.scenario().dual_stack {
+        return ConformanceOutcome::skip(
+            name,
+            SkipReason::NotConfigured,
+            "set scenario.dual_stack = true to probe both address families",
+        );
+    }
+
+    let addresses = match transport.resolve_target() {
+        Ok(addresses) => addresses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("target resolution failed: {error}"))
+        }
+    };
+    let ipv4 = addresses.iter().filter(|address| address.is_ipv4()).count();
+    let ipv6 = addresses.len() - ipv4;
+    if ipv4 == 0 || ipv6 == 0 {
+        return ConformanceOutcome::skip(
+            name,
+            SkipReason::NotApplicable,
+            format!("target publishes {ipv4} IPv4 and {ipv6} IPv6 addresses"),
+        );
+    }
+
+    let mut unreachable = Vec::new();
+    for address in &addresses {
+        match transport.request_raw_via(*address, &RawRequest::new("GET", "/healthz")) {
+            Ok(response) => {
+                let ok = response
+                    .json()
+                    .and_then(|payload| payload.get("ok").and_then(Value::as_bool))
+                    .unwrap_or(false);
+                if response.status != 200 || !ok {
+                    unreachable.push(format!("{address} (status={}, ok={ok})", response.status));
                 }
             }
+            Err(error) => unreachable.push(format!("{address} ({error})")),
         }
-        Err(error) => ConformanceOutcome {
+    }
+
+    if unreachable.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!("health endpoint answered on {ipv4} IPv4 and {ipv6} IPv6 addresses"),
+        )
+    } else {
+        ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("health endpoint request failed: {error}"),
+            format!(
+                "asymmetric reachability: health endpoint failed via {}",
+                unreachable.join(", ")
+            ),
+        )
+    }
+}
+
+fn run_healthz_date_within_clock_skew<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "healthz.date_within_clock_skew";
+    let max_skew_ms = ctx.scenario().max_clock_skew_ms;
+
+    let sent_ms = now_ms();
+    let response = match transport.request_raw(&RawRequest::new("GET", "/healthz")) {
+        Ok(response) => response,
+        Err(error) => {
+            return ConformanceOutcome::fail(
+                name,
+                format!("health endpoint request failed: {error}"),
+            )
+        }
+    };
+    let local_ms = sent_ms + (now_ms() - sent_ms) / 2;
+
+    // Date has second resolution, so the gateway clock lies somewhere in the following second.
+    let (source, earliest_ms, latest_ms) = match response.header("date") {
+        Some(date) => match httpdate::parse_http_date(date) {
+            Ok(time) => {
+                let date_ms = system_time_ms(time);
+                ("Date header", date_ms, date_ms + 999)
+            }
+            Err(error) => {
+                return ConformanceOutcome::fail(
+                    name,
+                    format!("health endpoint returned invalid Date header {date:?}: {error}"),
+                )
+            }
         },
+        None => match transport
+            .get_json("/info")
+            .ok()
+            .and_then(|info| info.get("ts").and_then(Value::as_i64))
+        {
+            Some(ts) => ("/info ts", i128::from(ts), i128::from(ts)),
+            None => {
+                return ConformanceOutcome::fail(
+                    name,
+                    "health endpoint returned no Date header and /info has no numeric ts",
+                )
+            }
+        },
+    };
+
+    let skew_ms = if local_ms < earliest_ms {
+        earliest_ms - local_ms
+    } else {
+        (local_ms - latest_ms).max(0)
+    };
+    if skew_ms <= i128::from(max_skew_ms) {
+        ConformanceOutcome::pass(
+            name,
+            format!("gateway clock ({source}) is within {skew_ms}ms of the runner clock"),
+        )
+    } else {
+        let direction = if local_ms < earliest_ms {
+            "ahead of"
+        } else {
+            "behind"
+        };
+        ConformanceOutcome::fail(
+            name,
+            format!(
+                "gateway clock ({source}) is {skew_ms}ms {direction} the runner clock, exceeding the {max_skew_ms}ms limit"
+            ),
+        )
     }
 }
 
@@ -142,59 +583,43 @@ fn run_readyz<T: ConformanceTransport>(transport: &T) -> ConformanceOutcome {
         Ok(payload) => {
             let ok = payload.get("ok").and_then(Value::as_bool).unwrap_or(false);
             if ok {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "ready endpoint returned ok=true".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "ready endpoint returned ok=true")
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: "ready endpoint did not return {\"ok\":true}".to_owned(),
-                }
+                ConformanceOutcome::fail(name, "ready endpoint did not return {\"ok\":true}")
             }
         }
-        Err(error) => ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!("ready endpoint request failed: {error}"),
-        },
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("ready endpoint request failed: {error}"))
+        }
     }
 }
 
-fn run_info_protocol_version<T: ConformanceTransport>(transport: &T) -> ConformanceOutcome {
+fn run_info_protocol_version<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
     let name = "info.protocol_version";
+    let expected = ctx.scenario().expected_protocol_version;
 
     match transport.get_json("/info") {
         Ok(payload) => {
             let actual = payload.get("protocolVersion").and_then(Value::as_u64);
             match actual {
-                Some(version) if version == EXPECTED_PROTOCOL_VERSION => ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: format!("protocolVersion={version}"),
-                },
-                Some(version) => ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
-                        "expected protocolVersion={}, found {version}",
-                        EXPECTED_PROTOCOL_VERSION
-                    ),
-                },
-                None => ConformanceOutcome {
+                Some(version) if version == expected => {
+                    ConformanceOutcome::pass(name, format!("protocolVersion={version}"))
+                }
+                Some(version) => ConformanceOutcome::fail(
                     name,
-                    passed: false,
-                    detail: "info endpoint missing numeric protocolVersion".to_owned(),
-                },
+                    format!("expected protocolVersion={expected}, found {version}"),
+                ),
+                None => {
+                    ConformanceOutcome::fail(name, "info endpoint missing numeric protocolVersion")
+                }
             }
         }
-        Err(error) => ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!("info endpoint request failed: {error}"),
-        },
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("info endpoint request failed: {error}"))
+        }
     }
 }
 
@@ -220,26 +645,19 @@ fn run_info_methods_include_health_and_status<T: ConformanceTransport>(
             let has_health = methods.iter().any(|method| method == "health");
             let has_status = methods.iter().any(|method| method == "status");
             if has_health && has_status {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "info.methods includes health and status".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "info.methods includes health and status")
             } else {
-                ConformanceOutcome {
+                ConformanceOutcome::fail(
                     name,
-                    passed: false,
-                    detail: format!(
+                    format!(
                         "expected info.methods to include health and status, found {methods:?}"
                     ),
-                }
+                )
             }
         }
-        Err(error) => ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!("info endpoint request failed: {error}"),
-        },
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("info endpoint request failed: {error}"))
+        }
     }
 }
 
@@ -257,26 +675,17 @@ fn run_unknown_channel_webhook_not_found<T: ConformanceTransport>(
                 .and_then(Value::as_str);
 
             if status == 404 && error_code == Some("NOT_FOUND") {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "unknown channel webhook returns 404 NOT_FOUND".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "unknown channel webhook returns 404 NOT_FOUND")
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
+                ConformanceOutcome::fail(name, format!(
                         "expected status=404 and error.code=NOT_FOUND, found status={status}, error.code={error_code:?}"
-                    ),
-                }
+                    ))
             }
         }
-        Err(error) => ConformanceOutcome {
+        Err(error) => ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("unknown channel webhook request failed: {error}"),
-        },
+            format!("unknown channel webhook request failed: {error}"),
+        ),
     }
 }
 
@@ -298,26 +707,16 @@ fn run_tools_invoke_gateway_request<T: ConformanceTransport>(transport: &T) -> C
                 .and_then(|result| result.get("ok"))
                 .and_then(Value::as_bool);
             if status == 200 && ok && result_ok == Some(true) {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "tools invoke gateway.request dispatches health".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "tools invoke gateway.request dispatches health")
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
+                ConformanceOutcome::fail(name, format!(
                         "expected status=200, ok=true, result.ok=true; found status={status}, ok={ok}, result.ok={result_ok:?}"
-                    ),
-                }
+                    ))
             }
         }
-        Err(error) => ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!("tools invoke request failed: {error}"),
-        },
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("tools invoke request failed: {error}"))
+        }
     }
 }
 
@@ -339,26 +738,20 @@ fn run_tools_invoke_gateway_request_action_fallback<T: ConformanceTransport>(
                 .and_then(|result| result.get("ok"))
                 .and_then(Value::as_bool);
             if status == 200 && ok && result_ok == Some(true) {
-                ConformanceOutcome {
+                ConformanceOutcome::pass(
                     name,
-                    passed: true,
-                    detail: "tools invoke accepts gateway.request action fallback".to_owned(),
-                }
+                    "tools invoke accepts gateway.request action fallback",
+                )
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
+                ConformanceOutcome::fail(name, format!(
                         "expected status=200, ok=true, result.ok=true; found status={status}, ok={ok}, result.ok={result_ok:?}"
-                    ),
-                }
+                    ))
             }
         }
-        Err(error) => ConformanceOutcome {
+        Err(error) => ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("tools invoke action fallback request failed: {error}"),
-        },
+            format!("tools invoke action fallback request failed: {error}"),
+        ),
     }
 }
 
@@ -379,29 +772,131 @@ fn run_tools_invoke_rejects_unknown_tool<T: ConformanceTransport>(
                 .and_then(|error| error.get("type"))
                 .and_then(Value::as_str);
             if status == 404 && !ok && error_type == Some("not_found") {
-                ConformanceOutcome {
+                ConformanceOutcome::pass(
                     name,
-                    passed: true,
-                    detail: "tools invoke rejects unknown tool names with 404 not_found".to_owned(),
-                }
+                    "tools invoke rejects unknown tool names with 404 not_found",
+                )
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
+                ConformanceOutcome::fail(name, format!(
                         "expected status=404, ok=false, error.type=not_found; found status={status}, ok={ok}, error.type={error_type:?}"
+                    ))
+            }
+        }
+        Err(error) => ConformanceOutcome::fail(
+            name,
+            format!("tools invoke unknown tool request failed: {error}"),
+        ),
+    }
+}
+
+fn run_tools_invoke_rejects_text_plain_content_type<T: ConformanceTransport>(
+    transport: &T,
+) -> ConformanceOutcome {
+    let name = "tools.invoke_rejects_text_plain_content_type";
+    let request = RawRequest::new("POST", "/tools/invoke")
+        .header("Content-Type", "text/plain")
+        .body(tools_invoke_health_body());
+
+    match transport.request_raw(&request) {
+        Ok(response) if matches!(response.status, 400 | 415) => ConformanceOutcome::pass(
+            name,
+            format!(
+                "tools invoke rejects text/plain bodies with status={}",
+                response.status
+            ),
+        ),
+        Ok(response) => ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected status=415 or 400 for Content-Type: text/plain, found status={}",
+                response.status
+            ),
+        ),
+        Err(error) => ConformanceOutcome::fail(
+            name,
+            format!("tools invoke text/plain request failed: {error}"),
+        ),
+    }
+}
+
+fn run_tools_invoke_rejects_missing_content_type<T: ConformanceTransport>(
+    transport: &T,
+) -> ConformanceOutcome {
+    let name = "tools.invoke_rejects_missing_content_type";
+    let request = RawRequest::new("POST", "/tools/invoke").body(tools_invoke_health_body());
+
+    match transport.request_raw(&request) {
+        Ok(response) if matches!(response.status, 400 | 415) => ConformanceOutcome::pass(
+            name,
+            format!(
+                "tools invoke rejects bodies without Content-Type with status={}",
+                response.status
+            ),
+        ),
+        Ok(response) => ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected status=415 or 400 without Content-Type, found status={}",
+                response.status
+            ),
+        ),
+        Err(error) => ConformanceOutcome::fail(
+            name,
+            format!("tools invoke missing Content-Type request failed: {error}"),
+        ),
+    }
+}
+
+fn run_tools_invoke_accepts_json_charset_content_type<T: ConformanceTransport>(
+    transport: &T,
+) -> ConformanceOutcome {
+    let name = "tools.invoke_accepts_json_charset_content_type";
+    let request = RawRequest::new("POST", "/tools/invoke")
+        .header("Content-Type", "application/json; charset=utf-8")
+        .body(tools_invoke_health_body());
+
+    match transport.request_raw(&request) {
+        Ok(response) => {
+            let body = response.json().unwrap_or(Value::Null);
+            let ok = body.get("ok").and_then(Value::as_bool).unwrap_or(false);
+            let result_ok = body
+                .get("result")
+                .and_then(|result| result.get("ok"))
+                .and_then(Value::as_bool);
+            if response.status == 200 && ok && result_ok == Some(true) {
+                ConformanceOutcome::pass(
+                    name,
+                    "tools invoke accepts application/json; charset=utf-8",
+                )
+            } else {
+                ConformanceOutcome::fail(
+                    name,
+                    format!(
+                        "expected status=200, ok=true, result.ok=true; found status={}, ok={ok}, result.ok={result_ok:?}",
+                        response.status
                     ),
-                }
+                )
             }
         }
-        Err(error) => ConformanceOutcome {
+        Err(error) => ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("tools invoke unknown tool request failed: {error}"),
-        },
+            format!("tools invoke charset request failed: {error}"),
+        ),
     }
 }
 
+fn tools_invoke_health_body() -> Vec<u8> {
+    serde_json::json!({
+        "tool": "gateway.request",
+        "args": {
+            "method": "health",
+            "params": {}
+        }
+    })
+    .to_string()
+    .into_bytes()
+}
+
 fn run_ws_handshake_requires_connect_first_frame<T: ConformanceTransport>(
     transport: &T,
 ) -> ConformanceOutcome {
@@ -422,35 +917,284 @@ fn run_ws_handshake_requires_connect_first_frame<T: ConformanceTransport>(
                 .and_then(Value::as_str);
 
             if !ok && code == Some("INVALID_REQUEST") {
-                ConformanceOutcome {
-                    name,
-                    passed: true,
-                    detail: "ws handshake rejects non-connect first request".to_owned(),
-                }
+                ConformanceOutcome::pass(name, "ws handshake rejects non-connect first request")
             } else {
-                ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!(
+                ConformanceOutcome::fail(name, format!(
                         "expected ok=false and error.code=INVALID_REQUEST, found ok={ok}, error.code={code:?}"
-                    ),
-                }
+                    ))
             }
         }
-        Err(error) => ConformanceOutcome {
+        Err(error) => {
+            ConformanceOutcome::fail(name, format!("websocket handshake request failed: {error}"))
+        }
+    }
+}
+
+/// One entry of the optional hello-ok `features` map.
+struct FeatureFlag {
+    name: String,
+    enabled: bool,
+    methods: Vec<String>,
+}
+
+/// Reads the `features` map from hello-ok; `Ok(None)` when the gateway does not send one.
+fn hello_features<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+    prefix: &str,
+) -> Result<Option<Vec<FeatureFlag>>, String> {
+    let request_id = ctx.unique_id(prefix);
+    let responses = transport
+        .websocket_exchange(&[ws_connect_frame(ctx, &format!("{request_id}-connect"))])
+        .map_err(|error| format!("websocket exchange failed: {error}"))?;
+    let Some(hello) = responses.first() else {
+        return Err("expected 1 websocket response, found 0".to_owned());
+    };
+    if hello.get("ok").and_then(Value::as_bool) != Some(true) {
+        return Err("connect was not accepted".to_owned());
+    }
+    let Some(features) = hello.pointer("/payload/features") else {
+        return Ok(None);
+    };
+    let Some(features) = features.as_object() else {
+        return Err(format!("hello-ok features is not an object: {features}"));
+    };
+
+    features
+        .iter()
+        .map(|(name, entry)| {
+            let enabled = entry.get("enabled").and_then(Value::as_bool);
+            let methods = entry.get("methods").and_then(Value::as_array).and_then(|methods| {
+                methods
+                    .iter()
+                    .map(|method| method.as_str().map(str::to_owned))
+                    .collect::<Option<Vec<_>>>()
+            });
+            match (enabled, methods) {
+                (Some(enabled), Some(methods)) => Ok(FeatureFlag {
+                    name: name.clone(),
+                    enabled,
+                    methods,
+                }),
+                _ => Err(format!(
+                    "hello-ok feature {name} needs a boolean enabled and a string array methods, found {entry}"
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// `(feature, method)` for every method of the features whose flag equals `enabled`.
+fn feature_methods(features: &[FeatureFlag], enabled: bool) -> Vec<(&str, &str)> {
+    features
+        .iter()
+        .filter(|feature| feature.enabled == enabled)
+        .flat_map(|feature| {
+            feature
+                .methods
+                .iter()
+                .map(|method| (feature.name.as_str(), method.as_str()))
+        })
+        .collect()
+}
+
+fn run_ws_feature_disabled_methods_rejected<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "ws.feature_disabled_methods_rejected";
+    let features = match hello_features(transport, ctx, "conformance-features-disabled") {
+        Ok(Some(features)) => features,
+        Ok(None) => {
+            return ConformanceOutcome::skip(
+                name,
+                SkipReason::NotApplicable,
+                "hello-ok carries no features map",
+            )
+        }
+        Err(detail) => return ConformanceOutcome::fail(name, detail),
+    };
+    let disabled = feature_methods(&features, false);
+    if disabled.is_empty() {
+        return ConformanceOutcome::skip(
             name,
-            passed: false,
-            detail: format!("websocket handshake request failed: {error}"),
-        },
+            SkipReason::NotApplicable,
+            "hello-ok reports no disabled features",
+        );
+    }
+
+    let responses =
+        match call_feature_methods(transport, ctx, &disabled, "conformance-features-call") {
+            Ok(responses) => responses,
+            Err(detail) => return ConformanceOutcome::fail(name, detail),
+        };
+
+    let mismatches = disabled
+        .iter()
+        .zip(&responses)
+        .filter_map(|((feature, method), response)| {
+            let ok = response.get("ok").and_then(Value::as_bool);
+            let code = response
+                .get("error")
+                .and_then(|error| error.get("code"))
+                .and_then(Value::as_str);
+            (ok != Some(false) || code != Some("FEATURE_DISABLED"))
+                .then(|| format!("{feature}/{method}: ok={ok:?}, code={code:?}"))
+        })
+        .collect::<Vec<_>>();
+
+    if mismatches.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!(
+                "{} methods of disabled features fail with FEATURE_DISABLED",
+                disabled.len()
+            ),
+        )
+    } else {
+        ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected FEATURE_DISABLED for disabled feature methods, found {}",
+                mismatches.join("; ")
+            ),
+        )
+    }
+}
+
+/// Error codes meaning the gateway does not serve a method at all.
+const UNKNOWN_METHOD_CODES: [&str; 2] = ["UNKNOWN_METHOD", "METHOD_NOT_FOUND"];
+
+/// Calls every method of `features` with empty params after `connect`, pairing each
+/// `(feature, method)` with its response.
+fn call_feature_methods<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+    methods: &[(&str, &str)],
+    prefix: &str,
+) -> Result<Vec<Value>, String> {
+    let request_id = ctx.unique_id(prefix);
+    let mut frames = vec![ws_connect_frame(ctx, &format!("{request_id}-connect"))];
+    frames.extend(methods.iter().enumerate().map(|(index, (_, method))| {
+        serde_json::json!({
+            "type": "req",
+            "id": format!("{request_id}-{}", index + 1),
+            "method": method,
+            "params": {}
+        })
+    }));
+    let mut responses = transport
+        .websocket_exchange(&frames)
+        .map_err(|error| format!("websocket exchange failed: {error}"))?;
+    if responses.len() != frames.len() {
+        return Err(format!(
+            "expected {} websocket responses, found {}",
+            frames.len(),
+            responses.len()
+        ));
+    }
+    responses.remove(0);
+    Ok(responses)
+}
+
+/// Enabled feature methods must be listed in `/info` methods, the gateway's capability probe,
+/// and must be served rather than rejected as disabled or unknown.
+fn run_ws_feature_enabled_methods_advertised<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "ws.feature_enabled_methods_advertised";
+    let features = match hello_features(transport, ctx, "conformance-features-enabled") {
+        Ok(Some(features)) => features,
+        Ok(None) => {
+            return ConformanceOutcome::skip(
+                name,
+                SkipReason::NotApplicable,
+                "hello-ok carries no features map",
+            )
+        }
+        Err(detail) => return ConformanceOutcome::fail(name, detail),
+    };
+    let enabled = feature_methods(&features, true);
+    if enabled.is_empty() {
+        return ConformanceOutcome::skip(
+            name,
+            SkipReason::NotApplicable,
+            "hello-ok reports no enabled features",
+        );
+    }
+
+    let info = match transport.get_json("/info") {
+        Ok(info) => info,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("info request failed: {error}"));
+        }
+    };
+    let Some(advertised) = info.get("methods").and_then(Value::as_array) else {
+        return ConformanceOutcome::fail(name, "/info is missing methods");
+    };
+    let missing = enabled
+        .iter()
+        .filter(|(_, method)| !advertised.iter().any(|item| item.as_str() == Some(method)))
+        .map(|(feature, method)| format!("{feature}/{method}"))
+        .collect::<Vec<_>>();
+
+    let responses = match call_feature_methods(
+        transport,
+        ctx,
+        &enabled,
+        "conformance-features-enabled-call",
+    ) {
+        Ok(responses) => responses,
+        Err(detail) => return ConformanceOutcome::fail(name, detail),
+    };
+    let rejected = enabled
+        .iter()
+        .zip(&responses)
+        .filter_map(|((feature, method), response)| {
+            let code = response
+                .get("error")
+                .and_then(|error| error.get("code"))
+                .and_then(Value::as_str)?;
+            (code == "FEATURE_DISABLED" || UNKNOWN_METHOD_CODES.contains(&code))
+                .then(|| format!("{feature}/{method}: code={code}"))
+        })
+        .collect::<Vec<_>>();
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!(
+            "enabled feature methods missing from /info methods: {}",
+            missing.join(", ")
+        ));
+    }
+    if !rejected.is_empty() {
+        problems.push(format!(
+            "enabled feature methods rejected as disabled or unknown: {}",
+            rejected.join(", ")
+        ));
+    }
+    if problems.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!(
+                "{} methods of enabled features are listed in /info and served",
+                enabled.len()
+            ),
+        )
+    } else {
+        ConformanceOutcome::fail(name, problems.join("; "))
     }
 }
 
 fn run_ws_channels_status_includes_account_views<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.channels_status_includes_account_views";
-    let run_id = unique_run_id("conformance-channels-status");
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let run_id = ctx.unique_id("conformance-channels-status");
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let status = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-status"),
@@ -458,22 +1202,22 @@ fn run_ws_channels_status_includes_account_views<T: ConformanceTransport>(
         "params": {}
     });
 
-    let responses = match transport.websocket_exchange(&[connect, status]) {
+    let frames = [connect, status];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 2 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 2 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -509,28 +1253,24 @@ fn run_ws_channels_status_includes_account_views<T: ConformanceTransport>(
         && webchat_default == Some("default")
         && webchat_connected == Some(true)
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "channels.status includes account-aware channel summary views".to_owned(),
-        }
+            "channels.status includes account-aware channel summary views",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected channel account views, found channels={has_channels_list}, order={has_channel_order}, labels={has_channel_labels}, byId={has_channels_by_id}, accounts={has_channel_accounts}, defaults={has_channel_default_account_id}, webchatDefault={webchat_default:?}, webchatConnected={webchat_connected:?}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_channels_logout_account_persists<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.channels_logout_account_persists";
-    let run_id = unique_run_id("conformance-channels-logout");
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let run_id = ctx.unique_id("conformance-channels-logout");
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let logout = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-logout"),
@@ -547,22 +1287,22 @@ fn run_ws_channels_logout_account_persists<T: ConformanceTransport>(
         "params": {}
     });
 
-    let responses = match transport.websocket_exchange(&[connect, logout, status]) {
+    let frames = [connect, logout, status];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 3 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 3 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -578,17 +1318,15 @@ fn run_ws_channels_logout_account_persists<T: ConformanceTransport>(
         .get("payload")
         .and_then(|payload| payload.get("accountId"))
         .and_then(Value::as_str);
-    let ops_persisted = responses[2]
-        .get("payload")
-        .and_then(|payload| payload.get("channelAccounts"))
-        .and_then(|payload| payload.get("webchat"))
-        .and_then(Value::as_array)
-        .is_some_and(|entries| {
-            entries.iter().any(|entry| {
-                entry.get("accountId").and_then(Value::as_str) == Some("ops")
-                    && entry.get("connected").and_then(Value::as_bool) == Some(false)
-            })
-        });
+    let ops_persisted = webchat_account_disconnected(&responses[2], "ops");
+    let logged_out_at = webchat_account(&responses[2], "ops")
+        .and_then(|entry| entry.get("loggedOutAtMs"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let logged_out_at_fresh = Matcher::TimestampWithin {
+        ms: ctx.scenario().max_clock_skew_ms,
+    }
+    .check(&logged_out_at);
     let webchat_connected = responses[2]
         .get("payload")
         .and_then(|payload| payload.get("channelsById"))
@@ -600,37 +1338,182 @@ fn run_ws_channels_logout_account_persists<T: ConformanceTransport>(
         && logout_ok
         && logout_account == Some("ops")
         && ops_persisted
+        && logged_out_at_fresh.is_ok()
         && webchat_connected == Some(true)
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "channels.logout(accountId) persists account-specific disconnected state"
-                .to_owned(),
-        }
+            "channels.logout(accountId) persists account-specific disconnected state",
+        )
     } else {
-        ConformanceOutcome {
+        ConformanceOutcome::fail(name, format!(
+                "expected account-aware logout persistence, found loggedOut={logout_ok}, accountId={logout_account:?}, opsPersisted={ops_persisted}, loggedOutAtMs={}, webchatConnected={webchat_connected:?}",
+                logged_out_at_fresh.err().unwrap_or_else(|| logged_out_at.to_string())
+            ))
+    }
+}
+
+/// Logs out an account, reads it back on the same connection, then polls `channels.status` from
+/// fresh connections until the logout is visible or the propagation tolerance elapses.
+fn run_ws_channels_logout_read_your_writes<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "ws.channels_logout_read_your_writes";
+    let run_id = ctx.unique_id("conformance-channels-ryw");
+    // The account is reused across runs so gateways that persist channel state keep a single
+    // entry; a logout left by an earlier run is told apart by its `loggedOutAtMs`.
+    let account_id = "conformance-ryw";
+    let status = |id: &str| {
+        serde_json::json!({
+            "type": "req",
+            "id": id,
+            "method": "channels.status",
+            "params": {}
+        })
+    };
+    let logout = serde_json::json!({
+        "type": "req",
+        "id": format!("{run_id}-logout"),
+        "method": "channels.logout",
+        "params": {
+            "channel": "webchat",
+            "accountId": account_id,
+        }
+    });
+
+    let frames = [
+        ws_connect_frame(ctx, &format!("{run_id}-connect")),
+        logout,
+        status(&format!("{run_id}-status")),
+    ];
+    let responses = match transport.websocket_exchange(&frames) {
+        Ok(responses) => responses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
+    }
+
+    let logout_ok = responses[1]
+        .get("payload")
+        .and_then(|payload| payload.get("loggedOut"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let same_connection = webchat_account_disconnected(&responses[2], account_id);
+    let logged_out_at = webchat_account(&responses[2], account_id)
+        .and_then(|entry| entry.get("loggedOutAtMs"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let fresh = Matcher::TimestampWithin {
+        ms: ctx.scenario().max_clock_skew_ms,
+    }
+    .check(&logged_out_at);
+    if !logout_ok || !same_connection || fresh.is_err() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!(
-                "expected account-aware logout persistence, found loggedOut={logout_ok}, accountId={logout_account:?}, opsPersisted={ops_persisted}, webchatConnected={webchat_connected:?}"
+            format!(
+                "expected logout visible on the same connection's next frame, found loggedOut={logout_ok}, visible={same_connection}, loggedOutAtMs={}",
+                fresh.err().unwrap_or_else(|| logged_out_at.to_string())
             ),
+        );
+    }
+    // A second connection must see this logout, not an older one of the same account.
+    let logged_out_at = logged_out_at.as_i64().unwrap_or_default();
+    let sees_logout = |status: &Value| {
+        webchat_account_disconnected(status, account_id)
+            && webchat_account(status, account_id)
+                .and_then(|entry| entry.get("loggedOutAtMs"))
+                .and_then(Value::as_i64)
+                .is_some_and(|at| at >= logged_out_at)
+    };
+
+    let tolerance = Duration::from_millis(ctx.scenario().propagation_tolerance_ms);
+    let interval = Duration::from_millis(ctx.scenario().propagation_poll_ms.max(1));
+    let started = Instant::now();
+    let mut polls = 0;
+    let visible_after = loop {
+        polls += 1;
+        let id = format!("{run_id}-status-{polls}");
+        match transport
+            .websocket_exchange(&[ws_connect_frame(ctx, &format!("{id}-connect")), status(&id)])
+        {
+            Ok(responses) if responses.len() == 2 && sees_logout(&responses[1]) => {
+                break Some(started.elapsed());
+            }
+            Ok(_) => {}
+            Err(error) => {
+                return ConformanceOutcome::fail(
+                    name,
+                    format!("second connection poll {polls} failed: {error}"),
+                );
+            }
         }
+
+        let elapsed = started.elapsed();
+        if elapsed >= tolerance {
+            break None;
+        }
+        thread::sleep(interval.min(tolerance - elapsed));
+    };
+
+    let tolerance_ms = tolerance.as_millis();
+    match visible_after {
+        Some(elapsed) => ConformanceOutcome::pass(
+            name,
+            format!(
+                "logout visible on the same connection's next frame and on a second connection after {}ms ({polls} polls, tolerance {tolerance_ms}ms)",
+                elapsed.as_millis()
+            ),
+        ),
+        None => ConformanceOutcome::fail(
+            name,
+            format!(
+                "logout visible on the same connection but not on a second connection within {tolerance_ms}ms ({polls} polls)"
+            ),
+        ),
     }
 }
 
+fn webchat_account_disconnected(status: &Value, account_id: &str) -> bool {
+    webchat_account(status, account_id)
+        .is_some_and(|entry| entry.get("connected").and_then(Value::as_bool) == Some(false))
+}
+
+/// Last `channelAccounts.webchat` entry for `account_id`.
+fn webchat_account<'a>(status: &'a Value, account_id: &str) -> Option<&'a Value> {
+    status
+        .get("payload")
+        .and_then(|payload| payload.get("channelAccounts"))
+        .and_then(|payload| payload.get("webchat"))
+        .and_then(Value::as_array)?
+        .iter()
+        .rev()
+        .find(|entry| entry.get("accountId").and_then(Value::as_str) == Some(account_id))
+}
+
 fn run_ws_agent_deferred_wait_completes<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.agent_deferred_wait_completes";
-    let run_id = unique_run_id("conformance-deferred");
+    let run_id = ctx.unique_id("conformance-deferred");
     let input = "conformance deferred";
     let session_key = format!("agent:main:{run_id}");
 
     let connect_id = format!("{run_id}-connect");
     let agent_id = format!("{run_id}-agent");
     let wait_id = format!("{run_id}-wait");
-    let connect = ws_connect_frame(&connect_id);
+    let connect = ws_connect_frame(ctx, &connect_id);
     let agent = serde_json::json!({
         "type": "req",
         "id": agent_id,
@@ -649,26 +1532,26 @@ fn run_ws_agent_deferred_wait_completes<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, agent, wait]) {
+    let frames = [connect, agent, wait];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 3 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 3 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -679,16 +1562,23 @@ fn run_ws_agent_deferred_wait_completes<T: ConformanceTransport>(
         .get("payload")
         .and_then(|payload| payload.get("summary"))
         .and_then(Value::as_str);
-    let final_status = responses[2]
+    let wait_response = match repoll_wait(transport, ctx, &run_id, &responses[2]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let final_status = wait_response
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let final_output = responses[2]
-        .get("payload")
-        .and_then(|payload| payload.get("result"))
-        .and_then(|result| result.get("output"))
-        .and_then(Value::as_str);
-    let final_session_key = responses[2]
+    let output_mismatch = ctx
+        .check_output(
+            "Echo: conformance deferred",
+            wait_response
+                .pointer("/payload/result/output")
+                .unwrap_or(&Value::Null),
+        )
+        .err();
+    let final_session_key = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("sessionKey"))
@@ -697,34 +1587,30 @@ fn run_ws_agent_deferred_wait_completes<T: ConformanceTransport>(
     if connect_ok
         && queued_summary == Some("queued")
         && final_status == Some("completed")
-        && final_output == Some("Echo: conformance deferred")
+        && output_mismatch.is_none()
         && final_session_key == Some(session_key.as_str())
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "deferred agent run transitions queued->completed via agent.wait".to_owned(),
-        }
+            "deferred agent run transitions queued->completed via agent.wait",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
-                "expected queued/completed deferred lifecycle, found summary={queued_summary:?}, status={final_status:?}, output={final_output:?}, sessionKey={final_session_key:?}"
-            ),
-        }
+        ConformanceOutcome::fail(name, format!(
+                "expected queued/completed deferred lifecycle, found summary={queued_summary:?}, status={final_status:?}, outputMismatch={output_mismatch:?}, sessionKey={final_session_key:?}"
+            ))
     }
 }
 
 fn run_ws_chat_send_deferred_wait_completes<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_send_deferred_wait_completes";
-    let run_id = unique_run_id("conformance-chat-deferred");
+    let run_id = ctx.unique_id("conformance-chat-deferred");
     let input = "conformance deferred chat";
     let session_key = format!("agent:main:{run_id}");
 
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let chat_send = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-chat-send"),
@@ -742,26 +1628,26 @@ fn run_ws_chat_send_deferred_wait_completes<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, chat_send, wait]) {
+    let frames = [connect, chat_send, wait];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 3 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 3 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -776,16 +1662,23 @@ fn run_ws_chat_send_deferred_wait_completes<T: ConformanceTransport>(
         .get("payload")
         .and_then(|payload| payload.get("message"))
         .is_some_and(Value::is_null);
-    let wait_status = responses[2]
+    let wait_response = match repoll_wait(transport, ctx, &run_id, &responses[2]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_status = wait_response
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let wait_output = responses[2]
-        .get("payload")
-        .and_then(|payload| payload.get("result"))
-        .and_then(|result| result.get("output"))
-        .and_then(Value::as_str);
-    let wait_session_key = responses[2]
+    let output_mismatch = ctx
+        .check_output(
+            "Echo: conformance deferred chat",
+            wait_response
+                .pointer("/payload/result/output")
+                .unwrap_or(&Value::Null),
+        )
+        .err();
+    let wait_session_key = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("sessionKey"))
@@ -795,38 +1688,33 @@ fn run_ws_chat_send_deferred_wait_completes<T: ConformanceTransport>(
         && queued_status == Some("queued")
         && queued_message_is_null
         && wait_status == Some("completed")
-        && wait_output == Some("Echo: conformance deferred chat")
+        && output_mismatch.is_none()
         && wait_session_key == Some(session_key.as_str())
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "deferred chat.send run transitions queued->completed via agent.wait"
-                .to_owned(),
-        }
+            "deferred chat.send run transitions queued->completed via agent.wait",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
-                "expected deferred chat.send lifecycle, found status={queued_status:?}, messageIsNull={queued_message_is_null}, waitStatus={wait_status:?}, waitOutput={wait_output:?}, sessionKey={wait_session_key:?}"
-            ),
-        }
+        ConformanceOutcome::fail(name, format!(
+                "expected deferred chat.send lifecycle, found status={queued_status:?}, messageIsNull={queued_message_is_null}, waitStatus={wait_status:?}, outputMismatch={output_mismatch:?}, sessionKey={wait_session_key:?}"
+            ))
     }
 }
 
 fn run_ws_chat_abort_cancels_deferred_run<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_cancels_deferred_run";
-    let run_id = unique_run_id("conformance-abort");
+    let run_id = ctx.unique_id("conformance-abort");
     let session_key = format!("agent:main:{run_id}");
 
     let connect_id = format!("{run_id}-connect");
     let agent_id = format!("{run_id}-agent");
     let abort_id = format!("{run_id}-abort");
     let wait_id = format!("{run_id}-wait");
-    let connect = ws_connect_frame(&connect_id);
+    let connect = ws_connect_frame(ctx, &connect_id);
     let agent = serde_json::json!({
         "type": "req",
         "id": agent_id,
@@ -854,26 +1742,26 @@ fn run_ws_chat_abort_cancels_deferred_run<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, agent, abort, wait]) {
+    let frames = [connect, agent, abort, wait];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 4 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 4 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -889,16 +1777,20 @@ fn run_ws_chat_abort_cancels_deferred_run<T: ConformanceTransport>(
         .and_then(|payload| payload.get("aborted"))
         .and_then(Value::as_bool)
         .unwrap_or(false);
-    let wait_status = responses[3]
+    let wait_response = match repoll_wait(transport, ctx, &run_id, &responses[3]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_status = wait_response
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let wait_output_is_null = responses[3]
+    let wait_output_is_null = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("output"))
         .is_some_and(Value::is_null);
-    let wait_session_key = responses[3]
+    let wait_session_key = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("sessionKey"))
@@ -911,30 +1803,26 @@ fn run_ws_chat_abort_cancels_deferred_run<T: ConformanceTransport>(
         && wait_output_is_null
         && wait_session_key == Some(session_key.as_str())
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "chat.abort cancels deferred run and agent.wait reports aborted".to_owned(),
-        }
+            "chat.abort cancels deferred run and agent.wait reports aborted",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected abort lifecycle, found summary={queued_summary:?}, aborted={abort_ok}, status={wait_status:?}, sessionKey={wait_session_key:?}, outputIsNull={wait_output_is_null}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_chat_abort_cancels_deferred_chat_send_run<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_cancels_deferred_chat_send_run";
-    let run_id = unique_run_id("conformance-chat-abort");
+    let run_id = ctx.unique_id("conformance-chat-abort");
     let session_key = format!("agent:main:{run_id}");
 
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let chat_send = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-chat-send"),
@@ -961,26 +1849,26 @@ fn run_ws_chat_abort_cancels_deferred_chat_send_run<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, chat_send, abort, wait]) {
+    let frames = [connect, chat_send, abort, wait];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 4 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 4 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -996,16 +1884,20 @@ fn run_ws_chat_abort_cancels_deferred_chat_send_run<T: ConformanceTransport>(
         .and_then(|payload| payload.get("aborted"))
         .and_then(Value::as_bool)
         .unwrap_or(false);
-    let wait_status = responses[3]
+    let wait_response = match repoll_wait(transport, ctx, &run_id, &responses[3]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_status = wait_response
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let wait_output_is_null = responses[3]
+    let wait_output_is_null = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("output"))
         .is_some_and(Value::is_null);
-    let wait_session_key = responses[3]
+    let wait_session_key = wait_response
         .get("payload")
         .and_then(|payload| payload.get("result"))
         .and_then(|result| result.get("sessionKey"))
@@ -1018,33 +1910,28 @@ fn run_ws_chat_abort_cancels_deferred_chat_send_run<T: ConformanceTransport>(
         && wait_output_is_null
         && wait_session_key == Some(session_key.as_str())
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "chat.abort cancels deferred chat.send run and agent.wait reports aborted"
-                .to_owned(),
-        }
+            "chat.abort cancels deferred chat.send run and agent.wait reports aborted",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected deferred chat.send abort lifecycle, found status={queued_status:?}, aborted={abort_ok}, waitStatus={wait_status:?}, waitOutputIsNull={wait_output_is_null}, sessionKey={wait_session_key:?}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_session_wide_cancels_deferred_chat_send_runs";
-    let session_id = unique_run_id("conformance-chat-abort-all");
+    let session_id = ctx.unique_id("conformance-chat-abort-all");
     let run_id_one = format!("{session_id}-one");
     let run_id_two = format!("{session_id}-two");
     let session_key = format!("agent:main:{session_id}");
 
-    let connect = ws_connect_frame(&format!("{session_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{session_id}-connect"));
     let first = serde_json::json!({
         "type": "req",
         "id": format!("{session_id}-chat-send-1"),
@@ -1081,7 +1968,7 @@ fn run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs<T: Conformance
         "method": "agent.wait",
         "params": {
             "runId": run_id_one,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
     let wait_two = serde_json::json!({
@@ -1090,27 +1977,23 @@ fn run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs<T: Conformance
         "method": "agent.wait",
         "params": {
             "runId": run_id_two,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses =
-        match transport.websocket_exchange(&[connect, first, second, abort, wait_one, wait_two]) {
-            Ok(responses) => responses,
-            Err(error) => {
-                return ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!("websocket exchange failed: {error}"),
-                };
-            }
-        };
+    let responses = match transport
+        .websocket_exchange(&[connect, first, second, abort, wait_one, wait_two])
+    {
+        Ok(responses) => responses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
     if responses.len() != 6 {
-        return ConformanceOutcome {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 6 websocket responses, found {}", responses.len()),
-        };
+            format!("expected 6 websocket responses, found {}", responses.len()),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1134,11 +2017,19 @@ fn run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs<T: Conformance
         .get("payload")
         .and_then(|payload| payload.get("runIds"))
         .and_then(Value::as_array);
-    let wait_one_status = responses[4]
+    let wait_one = match repoll_wait(transport, ctx, &run_id_one, &responses[4]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_one_status = wait_one
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let wait_two_status = responses[5]
+    let wait_two = match repoll_wait(transport, ctx, &run_id_two, &responses[5]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_two_status = wait_two
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
@@ -1163,33 +2054,28 @@ fn run_ws_chat_abort_session_wide_cancels_deferred_chat_send_runs<T: Conformance
         && wait_one_status == Some("aborted")
         && wait_two_status == Some("aborted")
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "chat.abort without runId cancels all session deferred chat.send runs"
-                .to_owned(),
-        }
+            "chat.abort without runId cancels all session deferred chat.send runs",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected session-wide deferred chat.send abort lifecycle, found queuedOne={queued_one:?}, queuedTwo={queued_two:?}, aborted={abort_ok}, hasRunOne={has_run_one}, hasRunTwo={has_run_two}, waitOne={wait_one_status:?}, waitTwo={wait_two_status:?}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_chat_abort_session_wide_cancels_runs<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_session_wide_cancels_runs";
-    let session_id = unique_run_id("conformance-abort-all");
+    let session_id = ctx.unique_id("conformance-abort-all");
     let run_id_one = format!("{session_id}-one");
     let run_id_two = format!("{session_id}-two");
     let session_key = format!("agent:main:{session_id}");
 
-    let connect = ws_connect_frame(&format!("{session_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{session_id}-connect"));
     let first = serde_json::json!({
         "type": "req",
         "id": format!("{session_id}-agent-1"),
@@ -1228,7 +2114,7 @@ fn run_ws_chat_abort_session_wide_cancels_runs<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id_one,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
     let wait_two = serde_json::json!({
@@ -1237,27 +2123,23 @@ fn run_ws_chat_abort_session_wide_cancels_runs<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id_two,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_schedule()[0]
         }
     });
 
-    let responses =
-        match transport.websocket_exchange(&[connect, first, second, abort, wait_one, wait_two]) {
-            Ok(responses) => responses,
-            Err(error) => {
-                return ConformanceOutcome {
-                    name,
-                    passed: false,
-                    detail: format!("websocket exchange failed: {error}"),
-                };
-            }
-        };
+    let responses = match transport
+        .websocket_exchange(&[connect, first, second, abort, wait_one, wait_two])
+    {
+        Ok(responses) => responses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
     if responses.len() != 6 {
-        return ConformanceOutcome {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 6 websocket responses, found {}", responses.len()),
-        };
+            format!("expected 6 websocket responses, found {}", responses.len()),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1281,11 +2163,19 @@ fn run_ws_chat_abort_session_wide_cancels_runs<T: ConformanceTransport>(
         .get("payload")
         .and_then(|payload| payload.get("runIds"))
         .and_then(Value::as_array);
-    let wait_one_status = responses[4]
+    let wait_one = match repoll_wait(transport, ctx, &run_id_one, &responses[4]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_one_status = wait_one
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
-    let wait_two_status = responses[5]
+    let wait_two = match repoll_wait(transport, ctx, &run_id_two, &responses[5]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let wait_two_status = wait_two
         .get("payload")
         .and_then(|payload| payload.get("status"))
         .and_then(Value::as_str);
@@ -1310,54 +2200,58 @@ fn run_ws_chat_abort_session_wide_cancels_runs<T: ConformanceTransport>(
         && wait_one_status == Some("aborted")
         && wait_two_status == Some("aborted")
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "chat.abort without runId cancels all session deferred runs".to_owned(),
-        }
+            "chat.abort without runId cancels all session deferred runs",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected session-wide abort lifecycle, found queuedOne={queued_one:?}, queuedTwo={queued_two:?}, aborted={abort_ok}, hasRunOne={has_run_one}, hasRunTwo={has_run_two}, waitOne={wait_one_status:?}, waitTwo={wait_two_status:?}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_agent_wait_timeout_for_missing_run<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.agent_wait_timeout_for_missing_run";
-    let run_id = unique_run_id("conformance-missing");
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let run_id = ctx.unique_id("conformance-missing");
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let wait = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-wait"),
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 50
+            "timeoutMs": ctx.probe_timeout_ms()
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, wait]) {
+    let frames = [connect, wait];
+    let setup_before = transport.connection_setup_time();
+    let started = Instant::now();
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 2 {
-        return ConformanceOutcome {
+    // Judge the exchange, not the handshake: a slow TLS or TCP setup says nothing about how
+    // long the gateway held agent.wait.
+    let setup = transport
+        .connection_setup_time()
+        .saturating_sub(setup_before);
+    let elapsed_ms = started.elapsed().saturating_sub(setup).as_millis() as u64;
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 2 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1374,30 +2268,207 @@ fn run_ws_agent_wait_timeout_for_missing_run<T: ConformanceTransport>(
         .and_then(Value::as_str);
 
     if connect_ok && wait_status == Some("timeout") && wait_run_id == Some(run_id.as_str()) {
-        ConformanceOutcome {
-            name,
-            passed: true,
-            detail: "agent.wait returns timeout for unknown run ids".to_owned(),
+        match ctx
+            .scenario()
+            .timing
+            .judge(ctx.probe_timeout_ms(), elapsed_ms)
+        {
+            Ok(timing) => ConformanceOutcome::pass(
+                name,
+                format!("agent.wait returns timeout for unknown run ids ({timing})"),
+            ),
+            Err(timing) => ConformanceOutcome::fail(
+                name,
+                format!("agent.wait returned timeout outside scenario.timing ({timing})"),
+            ),
         }
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected timeout for unknown run, found status={wait_status:?}, runId={wait_run_id:?}"
+            ))
+    }
+}
+
+fn run_ws_agent_wait_rejects_missing_run_id<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "ws.agent_wait_rejects_missing_run_id";
+    let request_id = ctx.unique_id("conformance-wait-params");
+    let connect = ws_connect_frame(ctx, &format!("{request_id}-connect"));
+    let missing = serde_json::json!({
+        "type": "req",
+        "id": format!("{request_id}-missing"),
+        "method": "agent.wait",
+        "params": {
+            "timeoutMs": ctx.probe_timeout_ms()
+        }
+    });
+    let non_string = serde_json::json!({
+        "type": "req",
+        "id": format!("{request_id}-non-string"),
+        "method": "agent.wait",
+        "params": {
+            "runId": 42,
+            "timeoutMs": ctx.probe_timeout_ms()
+        }
+    });
+
+    let frames = [connect, missing, non_string];
+    let responses = match transport.websocket_exchange(&frames) {
+        Ok(responses) => responses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
+    }
+
+    let connect_ok = responses[0]
+        .get("ok")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let problems = [
+        ("missing runId", &responses[1]),
+        ("numeric runId", &responses[2]),
+    ]
+    .into_iter()
+    .filter_map(|(case, response)| {
+        invalid_param_problem(response, "runId").map(|problem| format!("{case}: {problem}"))
+    })
+    .collect::<Vec<_>>();
+
+    if connect_ok && problems.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            "agent.wait rejects missing and non-string runId with INVALID_REQUEST",
+        )
+    } else if !connect_ok {
+        ConformanceOutcome::fail(name, "connect was not accepted")
+    } else {
+        ConformanceOutcome::fail(name, problems.join("; "))
+    }
+}
+
+/// `chat.send` with `message` absent (`None`), empty or null; the gateway must reject it rather
+/// than queue a run with no input.
+fn run_ws_chat_send_rejects_message<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+    name: &'static str,
+    message: Option<Value>,
+    expected: MessageExpectation,
+) -> ConformanceOutcome {
+    let run_id = ctx.unique_id("conformance-chat-message");
+    let mut params = serde_json::json!({
+        "sessionKey": format!("agent:main:{run_id}"),
+        "idempotencyKey": run_id,
+        "deferred": true,
+    });
+    if let Some(message) = message {
+        params["message"] = message;
+    }
+
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
+    let chat_send = serde_json::json!({
+        "type": "req",
+        "id": format!("{run_id}-chat-send"),
+        "method": "chat.send",
+        "params": params
+    });
+
+    let frames = [connect, chat_send];
+    let responses = match transport.websocket_exchange(&frames) {
+        Ok(responses) => responses,
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
+            name,
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
+    }
+
+    let connect_ok = responses[0]
+        .get("ok")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if !connect_ok {
+        return ConformanceOutcome::fail(name, "connect was not accepted");
+    }
+    if expected == MessageExpectation::Accept {
+        return match responses[1].get("ok").and_then(Value::as_bool) {
+            Some(true) => {
+                ConformanceOutcome::pass(name, "chat.send accepts the message as configured")
+            }
+            _ => ConformanceOutcome::fail(
+                name,
+                format!(
+                    "expected chat.send to accept the message as configured, found {}",
+                    responses[1]
+                ),
             ),
+        };
+    }
+    match invalid_param_problem(&responses[1], "message") {
+        None => {
+            ConformanceOutcome::pass(name, "chat.send rejects the message with INVALID_REQUEST")
         }
+        Some(problem) => ConformanceOutcome::fail(name, problem),
+    }
+}
+
+/// Why `response` is not an INVALID_REQUEST error naming `param`, if it is not.
+fn invalid_param_problem(response: &Value, param: &str) -> Option<String> {
+    if response.get("ok").and_then(Value::as_bool) != Some(false) {
+        let status = response
+            .get("payload")
+            .and_then(|payload| payload.get("status"))
+            .and_then(Value::as_str);
+        return Some(format!("expected ok false, found status={status:?}"));
+    }
+    let code = response
+        .get("error")
+        .and_then(|error| error.get("code"))
+        .and_then(Value::as_str);
+    let message = response
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str);
+    if code != Some("INVALID_REQUEST") {
+        Some(format!("expected INVALID_REQUEST, found code={code:?}"))
+    } else if !message.is_some_and(|message| message.contains(param)) {
+        Some(format!(
+            "expected error.message to name {param}, found {message:?}"
+        ))
+    } else {
+        None
     }
 }
 
 fn run_ws_chat_abort_rejects_run_session_mismatch<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_rejects_run_session_mismatch";
-    let run_id = unique_run_id("conformance-mismatch");
+    let run_id = ctx.unique_id("conformance-mismatch");
     let session_key = format!("agent:main:{run_id}");
 
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let agent = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-agent"),
@@ -1420,22 +2491,22 @@ fn run_ws_chat_abort_rejects_run_session_mismatch<T: ConformanceTransport>(
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, agent, abort]) {
+    let frames = [connect, agent, abort];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 3 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 3 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1460,30 +2531,26 @@ fn run_ws_chat_abort_rejects_run_session_mismatch<T: ConformanceTransport>(
         && !abort_ok
         && abort_error == Some("INVALID_REQUEST")
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
             name,
-            passed: true,
-            detail: "chat.abort rejects runId when sessionKey does not match".to_owned(),
-        }
+            "chat.abort rejects runId when sessionKey does not match",
+        )
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected INVALID_REQUEST on mismatched sessionKey, found queued={queued_summary:?}, ok={abort_ok}, code={abort_error:?}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_chat_abort_completed_run_noop<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_completed_run_noop";
-    let run_id = unique_run_id("conformance-completed");
+    let run_id = ctx.unique_id("conformance-completed");
     let session_key = format!("agent:main:{run_id}");
 
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let agent = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-agent"),
@@ -1502,7 +2569,7 @@ fn run_ws_chat_abort_completed_run_noop<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 2000
+            "timeoutMs": ctx.wait_budget_ms()
         }
     });
     let abort = serde_json::json!({
@@ -1515,22 +2582,22 @@ fn run_ws_chat_abort_completed_run_noop<T: ConformanceTransport>(
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, agent, wait, abort]) {
+    let frames = [connect, agent, wait, abort];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 4 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 4 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1566,31 +2633,24 @@ fn run_ws_chat_abort_completed_run_noop<T: ConformanceTransport>(
         && !abort_aborted
         && run_id_present
     {
-        ConformanceOutcome {
-            name,
-            passed: true,
-            detail: "chat.abort is a no-op for completed runs".to_owned(),
-        }
+        ConformanceOutcome::pass(name, "chat.abort is a no-op for completed runs")
     } else {
-        ConformanceOutcome {
-            name,
-            passed: false,
-            detail: format!(
+        ConformanceOutcome::fail(name, format!(
                 "expected completed-run abort no-op, found queued={queued_summary:?}, wait={wait_status:?}, aborted={abort_aborted}, runIdPresent={run_id_present}"
-            ),
-        }
+            ))
     }
 }
 
 fn run_ws_chat_abort_unknown_run_noop<T: ConformanceTransport>(
     transport: &T,
+    ctx: &RunContext,
 ) -> ConformanceOutcome {
     let name = "ws.chat_abort_unknown_run_noop";
-    let run_id = unique_run_id("conformance-abort-missing");
-    let session_id = unique_run_id("conformance-abort-missing-session");
+    let run_id = ctx.unique_id("conformance-abort-missing");
+    let session_id = ctx.unique_id("conformance-abort-missing-session");
     let session_key = format!("agent:main:{session_id}");
 
-    let connect = ws_connect_frame(&format!("{run_id}-connect"));
+    let connect = ws_connect_frame(ctx, &format!("{run_id}-connect"));
     let abort = serde_json::json!({
         "type": "req",
         "id": format!("{run_id}-abort"),
@@ -1606,26 +2666,26 @@ fn run_ws_chat_abort_unknown_run_noop<T: ConformanceTransport>(
         "method": "agent.wait",
         "params": {
             "runId": run_id,
-            "timeoutMs": 50,
+            "timeoutMs": ctx.probe_timeout_ms(),
         }
     });
 
-    let responses = match transport.websocket_exchange(&[connect, abort, wait]) {
+    let frames = [connect, abort, wait];
+    let responses = match transport.websocket_exchange(&frames) {
         Ok(responses) => responses,
         Err(error) => {
-            return ConformanceOutcome {
-                name,
-                passed: false,
-                detail: format!("websocket exchange failed: {error}"),
-            };
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
         }
     };
-    if responses.len() != 3 {
-        return ConformanceOutcome {
+    if responses.len() != frames.len() {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!("expected 3 websocket responses, found {}", responses.len()),
-        };
+            format!(
+                "expected {} websocket responses, found {}",
+                frames.len(),
+                responses.len()
+            ),
+        );
     }
 
     let connect_ok = responses[0]
@@ -1666,30 +2726,499 @@ fn run_ws_chat_abort_unknown_run_noop<T: ConformanceTransport>(
         && wait_status == Some("timeout")
         && wait_run_id == Some(run_id.as_str())
     {
-        ConformanceOutcome {
+        ConformanceOutcome::pass(
+            name,
+            "chat.abort unknown run is a no-op and agent.wait stays timeout",
+        )
+    } else {
+        ConformanceOutcome::fail(name, format!(
+                "expected unknown-run abort no-op, found abortOk={abort_ok}, aborted={abort_aborted}, runIdPresent={run_id_present}, waitStatus={wait_status:?}, waitRunId={wait_run_id:?}"
+            ))
+    }
+}
+
+/// Completes a run, restarts the gateway through the configured hook, then checks that health and
+/// readiness recover within the deadline and whether the completed run is still retrievable.
+/// How long a failed restart hook's stderr is collected after it exits.
+const HOOK_STDERR_GRACE: Duration = Duration::from_millis(200);
+
+fn run_restart_recovers_health_and_runs<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "restart.recovers_health_and_runs";
+    let restart = &ctx.scenario().restart;
+    let Some((program, hook_args)) = restart.hook.split_first() else {
+        return ConformanceOutcome::skip(
+            name,
+            SkipReason::NotConfigured,
+            "set scenario.restart.hook to a command that restarts the gateway",
+        );
+    };
+
+    let run_id = ctx.unique_id("conformance-restart");
+    let frames = [
+        ws_connect_frame(ctx, &format!("{run_id}-connect")),
+        serde_json::json!({
+            "type": "req",
+            "id": format!("{run_id}-agent"),
+            "method": "agent",
+            "params": {
+                "runId": run_id,
+                "sessionKey": format!("agent:main:{run_id}"),
+                "agentId": "main",
+                "input": "conformance restart",
+                "deferred": true,
+            }
+        }),
+        serde_json::json!({
+            "type": "req",
+            "id": format!("{run_id}-wait"),
+            "method": "agent.wait",
+            "params": {
+                "runId": run_id,
+                "timeoutMs": ctx.wait_schedule()[0]
+            }
+        }),
+    ];
+    let responses = match transport.websocket_exchange(&frames) {
+        Ok(responses) if responses.len() == frames.len() => responses,
+        Ok(responses) => {
+            return ConformanceOutcome::fail(
+                name,
+                format!(
+                    "expected {} websocket responses, found {}",
+                    frames.len(),
+                    responses.len()
+                ),
+            );
+        }
+        Err(error) => {
+            return ConformanceOutcome::fail(name, format!("websocket exchange failed: {error}"));
+        }
+    };
+    let wait_response = match repoll_wait(transport, ctx, &run_id, &responses[2]) {
+        Ok(response) => response,
+        Err(error) => return ConformanceOutcome::fail(name, error),
+    };
+    let before_status = wait_response
+        .pointer("/payload/status")
+        .and_then(Value::as_str);
+    if before_status != Some("completed") {
+        return ConformanceOutcome::fail(
+            name,
+            format!("could not complete a run before the restart, status={before_status:?}"),
+        );
+    }
+
+    let marker_before = instance_marker(transport);
+    let mut hook = match Command::new(program)
+        .args(hook_args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(hook) => hook,
+        Err(error) => {
+            return ConformanceOutcome::fail(
+                name,
+                format!("restart hook {program:?} failed to start: {error}"),
+            );
+        }
+    };
+    // Lines arrive over a channel rather than through a joined thread: a gateway the hook
+    // started in the background inherits the pipe and may hold it open indefinitely.
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(stderr) = hook.stderr.take() {
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                if stderr_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    // Health is polled while the hook runs so downtime is seen even when the hook only
+    // returns once the gateway is back.
+    let interval = Duration::from_millis(restart.poll_ms.max(1));
+    let hook_deadline = Duration::from_millis(restart.hook_timeout_ms);
+    let hook_started = Instant::now();
+    let mut downtime = false;
+    let mut last_poll: Option<Instant> = None;
+    let status = loop {
+        match hook.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(error) => {
+                let _ = hook.kill();
+                return ConformanceOutcome::fail(
+                    name,
+                    format!("restart hook {program:?} could not be awaited: {error}"),
+                );
+            }
+        }
+        if hook_started.elapsed() >= hook_deadline {
+            let _ = hook.kill();
+            let _ = hook.wait();
+            return ConformanceOutcome::fail(
+                name,
+                format!(
+                    "restart hook {program:?} did not exit within {}ms and was killed",
+                    hook_deadline.as_millis()
+                ),
+            );
+        }
+        if last_poll.is_none_or(|polled| polled.elapsed() >= interval) {
+            downtime |= !endpoint_ok(transport, "/healthz");
+            last_poll = Some(Instant::now());
+        }
+        thread::sleep(interval.min(Duration::from_millis(10)));
+    };
+    if !status.success() {
+        let remaining = hook_deadline.saturating_sub(hook_started.elapsed());
+        let collect_until = Instant::now() + remaining.min(HOOK_STDERR_GRACE);
+        let mut stderr = Vec::new();
+        while let Ok(line) =
+            stderr_rx.recv_timeout(collect_until.saturating_duration_since(Instant::now()))
+        {
+            stderr.push(line);
+        }
+        return ConformanceOutcome::fail(
             name,
-            passed: true,
-            detail: "chat.abort unknown run is a no-op and agent.wait stays timeout".to_owned(),
+            format!(
+                "restart hook {program:?} exited with {status}: {}",
+                stderr.join("\n").trim()
+            ),
+        );
+    }
+
+    let deadline = Duration::from_millis(restart.recovery_deadline_ms);
+    let started = Instant::now();
+    let mut healthy_after = None;
+    let mut ready_after = None;
+    loop {
+        if healthy_after.is_none() {
+            if endpoint_ok(transport, "/healthz") {
+                healthy_after = Some(started.elapsed());
+            } else {
+                downtime = true;
+            }
+        }
+        if healthy_after.is_some() && endpoint_ok(transport, "/readyz") {
+            ready_after = Some(started.elapsed());
+            break;
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= deadline {
+            break;
         }
+        thread::sleep(interval.min(deadline - elapsed));
+    }
+    let (Some(healthy_after), Some(ready_after)) = (healthy_after, ready_after) else {
+        return ConformanceOutcome::fail(
+            name,
+            format!(
+                "gateway did not recover within {}ms of the restart hook, healthz={}, readyz=false",
+                deadline.as_millis(),
+                healthy_after.is_some()
+            ),
+        );
+    };
+    let marker_after = instance_marker(transport);
+    let evidence = if downtime {
+        "downtime observed"
+    } else if marker_before.is_some() && marker_after != marker_before {
+        "/info instance marker changed"
     } else {
-        ConformanceOutcome {
+        return ConformanceOutcome::fail(
             name,
-            passed: false,
-            detail: format!(
-                "expected unknown-run abort no-op, found abortOk={abort_ok}, aborted={abort_aborted}, runIdPresent={run_id_present}, waitStatus={wait_status:?}, waitRunId={wait_run_id:?}"
+            format!(
+                "restart hook {program:?} exited successfully, but /healthz never failed and /info instanceId/startedAt did not change ({marker_before:?}), so no restart was observed"
+            ),
+        );
+    };
+    let recovery = format!(
+        "{evidence}; healthz recovered after {}ms and readyz after {}ms",
+        healthy_after.as_millis(),
+        ready_after.as_millis()
+    );
+
+    let id = format!("{run_id}-wait-after-restart");
+    let wait = serde_json::json!({
+        "type": "req",
+        "id": id,
+        "method": "agent.wait",
+        "params": {
+            "runId": run_id,
+            "timeoutMs": ctx.probe_timeout_ms()
+        }
+    });
+    let after_status = match transport
+        .websocket_exchange(&[ws_connect_frame(ctx, &format!("{id}-connect")), wait])
+    {
+        Ok(responses) => responses
+            .get(1)
+            .and_then(|response| response.pointer("/payload/status"))
+            .and_then(Value::as_str)
+            .map(str::to_owned),
+        Err(error) => {
+            return ConformanceOutcome::fail(
+                name,
+                format!("{recovery}, but agent.wait after the restart failed: {error}"),
+            );
+        }
+    };
+
+    match (after_status.as_deref(), restart.runs_persist) {
+        (Some("completed"), _) => ConformanceOutcome::pass(
+            name,
+            format!("{recovery}; completed run is still retrievable"),
+        ),
+        (_, false) => ConformanceOutcome::pass(
+            name,
+            format!(
+                "{recovery}; completed run is not retrievable after the restart (agent.wait status={after_status:?}), as documented by scenario.restart.runs_persist = false"
+            ),
+        ),
+        (_, true) => ConformanceOutcome::fail(
+            name,
+            format!(
+                "{recovery}, but the completed run is not retrievable after the restart (agent.wait status={after_status:?})"
+            ),
+        ),
+    }
+}
+
+/// `/info` `instanceId`, or `startedAt` when absent, which changes when the gateway restarts.
+fn instance_marker<T: ConformanceTransport>(transport: &T) -> Option<String> {
+    let info = transport.get_json("/info").ok()?;
+    info.get("instanceId")
+        .or_else(|| info.get("startedAt"))
+        .map(Value::to_string)
+}
+
+fn endpoint_ok<T: ConformanceTransport>(transport: &T, path: &str) -> bool {
+    transport
+        .get_json(path)
+        .is_ok_and(|payload| payload.get("ok").and_then(Value::as_bool) == Some(true))
+}
+
+/// Legacy `channels` entries must mirror every `channelsById` entry's `connected` state.
+fn run_compat_channels_status_legacy_channels_array<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "compat.channels_status_legacy_channels_array";
+    let payload = match channels_status_payload(transport, ctx, "conformance-compat-channels") {
+        Ok(payload) => payload,
+        Err(detail) => return ConformanceOutcome::fail(name, detail),
+    };
+
+    let Some(by_id) = payload.get("channelsById").and_then(Value::as_object) else {
+        return ConformanceOutcome::fail(name, "channels.status is missing v3 channelsById");
+    };
+    let Some(legacy) = payload.get("channels").and_then(Value::as_array) else {
+        return ConformanceOutcome::fail(
+            name,
+            "channels.status is missing the legacy v2 channels array",
+        );
+    };
+
+    let mut mismatches = Vec::new();
+    for (id, entry) in by_id {
+        let connected = entry.get("connected").and_then(Value::as_bool);
+        let legacy_connected = legacy
+            .iter()
+            .find(|item| {
+                item.get("id").and_then(Value::as_str) == Some(id.as_str())
+                    && item.get("accountId").is_none()
+            })
+            .map(|item| item.get("connected").and_then(Value::as_bool));
+        match legacy_connected {
+            None => mismatches.push(format!("{id}: missing from channels")),
+            Some(legacy_connected) if legacy_connected != connected => mismatches.push(format!(
+                "{id}: channels connected={legacy_connected:?}, channelsById connected={connected:?}"
+            )),
+            Some(_) => {}
+        }
+    }
+
+    if by_id.is_empty() {
+        ConformanceOutcome::fail(name, "channelsById is empty; nothing to compare")
+    } else if mismatches.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!(
+                "legacy channels array mirrors {} channelsById entries",
+                by_id.len()
+            ),
+        )
+    } else {
+        ConformanceOutcome::fail(
+            name,
+            format!(
+                "legacy channels array disagrees with channelsById: {}",
+                mismatches.join("; ")
+            ),
+        )
+    }
+}
+
+/// Legacy `channelLabels` and `channelOrder` must cover every `channelMeta` entry.
+fn run_compat_channels_status_legacy_channel_labels<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+) -> ConformanceOutcome {
+    let name = "compat.channels_status_legacy_channel_labels";
+    let payload = match channels_status_payload(transport, ctx, "conformance-compat-labels") {
+        Ok(payload) => payload,
+        Err(detail) => return ConformanceOutcome::fail(name, detail),
+    };
+
+    let Some(meta) = payload.get("channelMeta").and_then(Value::as_object) else {
+        return ConformanceOutcome::fail(name, "channels.status is missing v3 channelMeta");
+    };
+    let labels = payload.get("channelLabels").and_then(Value::as_object);
+    let order = payload.get("channelOrder").and_then(Value::as_array);
+    let (Some(labels), Some(order)) = (labels, order) else {
+        return ConformanceOutcome::fail(
+            name,
+            format!(
+                "channels.status is missing legacy v2 fields, found channelLabels={}, channelOrder={}",
+                labels.is_some(),
+                order.is_some()
+            ),
+        );
+    };
+
+    let mut mismatches = Vec::new();
+    for (id, entry) in meta {
+        let label = entry.get("label").and_then(Value::as_str);
+        let legacy_label = labels.get(id).and_then(Value::as_str);
+        if legacy_label != label {
+            mismatches.push(format!(
+                "{id}: channelLabels={legacy_label:?}, channelMeta label={label:?}"
+            ));
+        }
+        if !order.iter().any(|item| item.as_str() == Some(id.as_str())) {
+            mismatches.push(format!("{id}: missing from channelOrder"));
+        }
+    }
+
+    if meta.is_empty() {
+        ConformanceOutcome::fail(name, "channelMeta is empty; nothing to compare")
+    } else if mismatches.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!(
+                "legacy channelLabels and channelOrder cover {} channelMeta entries",
+                meta.len()
+            ),
+        )
+    } else {
+        ConformanceOutcome::fail(
+            name,
+            format!(
+                "legacy channel labels disagree with channelMeta: {}",
+                mismatches.join("; ")
             ),
+        )
+    }
+}
+
+/// Connects and returns the `channels.status` payload, or a failure detail.
+fn channels_status_payload<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+    prefix: &str,
+) -> Result<Value, String> {
+    let run_id = ctx.unique_id(prefix);
+    let frames = [
+        ws_connect_frame(ctx, &format!("{run_id}-connect")),
+        serde_json::json!({
+            "type": "req",
+            "id": format!("{run_id}-status"),
+            "method": "channels.status",
+            "params": {}
+        }),
+    ];
+    let responses = transport
+        .websocket_exchange(&frames)
+        .map_err(|error| format!("websocket exchange failed: {error}"))?;
+    if responses.len() != frames.len() {
+        return Err(format!(
+            "expected {} websocket responses, found {}",
+            frames.len(),
+            responses.len()
+        ));
+    }
+    if responses[0].get("ok").and_then(Value::as_bool) != Some(true) {
+        return Err("connect was not accepted".to_owned());
+    }
+    responses[1]
+        .get("payload")
+        .filter(|payload| payload.is_object())
+        .cloned()
+        .ok_or_else(|| "channels.status returned no payload object".to_owned())
+}
+
+/// Re-polls `agent.wait` on fresh connections while the run is still pending, following the
+/// configured wait strategy. Returns the last wait response observed, or why a re-poll could
+/// not be made.
+fn repoll_wait<T: ConformanceTransport>(
+    transport: &T,
+    ctx: &RunContext,
+    run_id: &str,
+    response: &Value,
+) -> Result<Value, String> {
+    let mut response = response.clone();
+    for (attempt, timeout_ms) in ctx.wait_schedule().into_iter().enumerate().skip(1) {
+        let status = response
+            .get("payload")
+            .and_then(|payload| payload.get("status"))
+            .and_then(Value::as_str);
+        if status != Some("timeout") {
+            break;
+        }
+
+        let id = format!("{run_id}-wait-retry-{attempt}");
+        let wait = serde_json::json!({
+            "type": "req",
+            "id": id,
+            "method": "agent.wait",
+            "params": {
+                "runId": run_id,
+                "timeoutMs": timeout_ms
+            }
+        });
+        match transport.websocket_exchange(&[ws_connect_frame(ctx, &format!("{id}-connect")), wait])
+        {
+            Ok(mut responses) if responses.len() == 2 => response = responses.swap_remove(1),
+            Ok(responses) => {
+                return Err(format!(
+                    "agent.wait re-poll {attempt} for {run_id}: expected 2 websocket responses, found {}",
+                    responses.len()
+                ));
+            }
+            Err(error) => {
+                return Err(format!(
+                    "agent.wait re-poll {attempt} for {run_id} failed: {error}"
+                ));
+            }
         }
     }
+    Ok(response)
 }
 
-fn ws_connect_frame(id: &str) -> Value {
+fn ws_connect_frame(ctx: &RunContext, id: &str) -> Value {
     serde_json::json!({
         "type": "req",
         "id": id,
         "method": "connect",
         "params": {
-            "minProtocol": 1,
-            "maxProtocol": 3,
+            "minProtocol": ctx.scenario().min_protocol,
+            "maxProtocol": ctx.scenario().max_protocol,
             "client": {
                 "id": "reclaw-conformance",
                 "displayName": "Reclaw Conformance",
@@ -1706,10 +3235,42 @@ fn ws_connect_frame(id: &str) -> Value {
     })
 }
 
-fn unique_run_id(prefix: &str) -> String {
-    let now_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|value| value.as_millis())
-        .unwrap_or(0);
-    format!("{prefix}-{now_ms}")
+fn now_ms() -> i128 {
+    system_time_ms(SystemTime::now())
+}
+
+fn system_time_ms(time: SystemTime) -> i128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|value| value.as_millis() as i128)
+        .unwrap_or(0)
+}
+
+/// Audits every identifier earlier scenarios in the run generated; must run last.
+fn run_identifier_isolation(ctx: &RunContext) -> ConformanceOutcome {
+    let name = "run.identifier_isolation";
+    if ctx.generated_ids() == 0 {
+        return ConformanceOutcome::skip(
+            name,
+            SkipReason::NotApplicable,
+            "no scenario in this run generated identifiers to audit",
+        );
+    }
+    let echoes = ctx.echoes();
+    if echoes.is_empty() {
+        ConformanceOutcome::pass(
+            name,
+            format!(
+                "{} generated identifiers stayed within their scenarios",
+                ctx.generated_ids()
+            ),
+        )
+    } else {
+        ConformanceOutcome::fail(
+            name,
+            format!(
+                "gateway echoed identifiers across scenarios: {}",
+                echoes.join("; ")
+            ),
+        )
+    }
 }
@@ -0,0 +1,168 @@
+use std::{
+    cell::Cell,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{config::TraceConfig, TransportError};
+
+/// Gateway profile that accepts unknown WS frame fields, required for `trace.ws_frames`.
+pub(crate) const FRAME_TRACE_PROFILE: &str = "ws-frame-trace";
+
+/// Generates W3C trace context for outgoing requests; every request gets a fresh span id under
+/// one trace id per transport.
+pub(crate) struct Tracer {
+    trace_id: String,
+    flags: String,
+    tracestate: Option<String>,
+    ws_frames: bool,
+    /// Per-tracer entropy, so span ids differ between runs that continue the same parent.
+    seed: String,
+    spans: Cell<u64>,
+}
+
+impl Tracer {
+    /// `ws_frames` is only honoured when `frame_fields` says the gateway accepts them.
+    pub(crate) fn new(config: &TraceConfig, frame_fields: bool) -> Result<Self, TransportError> {
+        let seed = format!(
+            "{}-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|value| value.as_nanos())
+                .unwrap_or(0),
+            process::id()
+        );
+        let (trace_id, flags) = match &config.parent {
+            Some(parent) => parse_traceparent(parent)?,
+            None => (hex(&Sha256::digest(seed.as_bytes())[..16]), "01".to_owned()),
+        };
+
+        Ok(Self {
+            trace_id,
+            flags,
+            tracestate: config.tracestate.clone(),
+            ws_frames: config.ws_frames && frame_fields,
+            seed,
+            spans: Cell::new(0),
+        })
+    }
+
+    pub(crate) fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// `traceparent` and, when configured, `tracestate` for one new span.
+    pub(crate) fn headers(&self) -> Vec<(&'static str, String)> {
+        let span = self.spans.get() + 1;
+        self.spans.set(span);
+        let span_id =
+            hex(&Sha256::digest(format!("{}-{}-{span}", self.seed, self.trace_id).as_bytes())[..8]);
+
+        let mut headers = vec![(
+            "traceparent",
+            format!("00-{}-{span_id}-{}", self.trace_id, self.flags),
+        )];
+        if let Some(tracestate) = &self.tracestate {
+            headers.push(("tracestate", tracestate.clone()));
+        }
+        headers
+    }
+
+    /// Adds a `trace` field carrying the trace headers to an object frame, if enabled.
+    pub(crate) fn annotate_frame(&self, frame: &Value) -> Value {
+        let mut frame = frame.clone();
+        if let (true, Some(fields)) = (self.ws_frames, frame.as_object_mut()) {
+            let trace = self
+                .headers()
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), Value::String(value)))
+                .collect();
+            fields.insert("trace".to_owned(), Value::Object(trace));
+        }
+        frame
+    }
+}
+
+fn parse_traceparent(value: &str) -> Result<(String, String), TransportError> {
+    let parts = value.trim().split('-').collect::<Vec<_>>();
+    let is_hex = |part: &str, len: usize| {
+        part.len() == len
+            && part
+                .chars()
+                .all(|ch| ch.is_ascii_digit() || ('a'..='f').contains(&ch))
+    };
+    match parts.as_slice() {
+        [version, trace_id, parent_id, flags]
+            if is_hex(version, 2)
+                && is_hex(trace_id, 32)
+                && is_hex(parent_id, 16)
+                && is_hex(flags, 2)
+                && *trace_id != "0".repeat(32) =>
+        {
+            Ok(((*trace_id).to_owned(), (*flags).to_owned()))
+        }
+        _ => Err(TransportError::Protocol(format!(
+            "invalid traceparent {value:?}"
+        ))),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::config::TraceConfig;
+
+    use super::Tracer;
+
+    #[test]
+    fn tracer_continues_parent_trace_with_fresh_spans() {
+        let config = TraceConfig {
+            enabled: true,
+            parent: Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_owned()),
+            tracestate: Some("runner=conformance".to_owned()),
+            ws_frames: true,
+        };
+        let tracer = Tracer::new(&config, true).expect("parent should parse");
+
+        let first = tracer.headers();
+        let second = tracer.headers();
+        assert!(first[0]
+            .1
+            .starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(first[0].1.ends_with("-01"));
+        assert_ne!(first[0].1, second[0].1);
+        assert_eq!(first[1], ("tracestate", "runner=conformance".to_owned()));
+
+        let frame = tracer.annotate_frame(&json!({ "method": "health" }));
+        assert_eq!(frame["trace"]["tracestate"], "runner=conformance");
+
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let rerun = Tracer::new(&config, false).expect("parent should parse");
+        assert_ne!(rerun.headers()[0].1, first[0].1);
+        let frame = rerun.annotate_frame(&json!({ "method": "health" }));
+        assert!(frame.get("trace").is_none());
+    }
+
+    #[test]
+    fn tracer_rejects_malformed_parent() {
+        let error = Tracer::new(
+            &TraceConfig {
+                enabled: true,
+                parent: Some("00-xyz-00f067aa0ba902b7-01".to_owned()),
+                ..TraceConfig::default()
+            },
+            false,
+        )
+        .err()
+        .expect("parent should be rejected");
+        assert!(error.to_string().contains("invalid traceparent"));
+    }
+}
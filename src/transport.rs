@@ -1,22 +1,179 @@
-use std::net::TcpStream;
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    io::{ErrorKind, Read, Write},
+    net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use reqwest::{blocking::Client, StatusCode};
+use reqwest::{blocking::Client, Method, StatusCode, Url};
+use rustls::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore, StreamOwned};
 use serde_json::Value;
 use thiserror::Error;
-use tungstenite::{connect, stream::MaybeTlsStream, Message, WebSocket};
+use tungstenite::{
+    client::IntoClientRequest, client_tls, http::HeaderValue, stream::MaybeTlsStream, Message,
+    WebSocket,
+};
+
+use crate::{
+    trace::{Tracer, FRAME_TRACE_PROFILE},
+    ConformanceConfig,
+};
 
 pub trait ConformanceTransport {
     fn get_json(&self, path: &str) -> Result<Value, TransportError>;
     fn post_json(&self, path: &str, body: &Value) -> Result<(u16, Value), TransportError>;
     fn websocket_first_response(&self, frame: &Value) -> Result<Value, TransportError>;
     fn websocket_exchange(&self, frames: &[Value]) -> Result<Vec<Value>, TransportError>;
+    fn request_raw(&self, _request: &RawRequest) -> Result<RawResponse, TransportError> {
+        Err(TransportError::Protocol("unsupported".to_owned()))
+    }
+    /// Addresses the target host resolves to, deduplicated in resolver order.
+    fn resolve_target(&self) -> Result<Vec<IpAddr>, TransportError> {
+        Err(TransportError::Protocol("unsupported".to_owned()))
+    }
+    /// Sends `request` to the target host over a connection to `address`.
+    fn request_raw_via(
+        &self,
+        _address: IpAddr,
+        _request: &RawRequest,
+    ) -> Result<RawResponse, TransportError> {
+        Err(TransportError::Protocol("unsupported".to_owned()))
+    }
+    /// Cumulative time spent establishing connections, for profiling. Transports that cannot
+    /// separate setup from request time report zero.
+    fn connection_setup_time(&self) -> Duration {
+        Duration::ZERO
+    }
+    /// Wire sizes of the JSON bodies and frames returned since the last call, in order, for
+    /// byte accounting. Transports that cannot measure them return `None`.
+    fn take_response_sizes(&self) -> Option<Vec<u64>> {
+        None
+    }
+}
+
+/// An HTTP request sent as-is, for scenarios that probe methods, headers, or bodies the JSON
+/// helpers normalize away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl RawRequest {
+    pub fn new(method: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RawResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Address family used to reach the target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpVersion {
+    V4,
+    V6,
+    /// Let the resolver and connector choose.
+    #[default]
+    Auto,
+}
+
+impl IpVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V4 => "4",
+            Self::V6 => "6",
+            Self::Auto => "auto",
+        }
+    }
+
+    pub fn matches(&self, address: IpAddr) -> bool {
+        match self {
+            Self::V4 => address.is_ipv4(),
+            Self::V6 => address.is_ipv6(),
+            Self::Auto => true,
+        }
+    }
+}
+
+impl fmt::Display for IpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for IpVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "4" => Ok(Self::V4),
+            "6" => Ok(Self::V6),
+            "auto" => Ok(Self::Auto),
+            _ => Err(format!("expected 4, 6, or auto, found {value}")),
+        }
+    }
 }
 
 pub struct HttpTransport {
     base_url: String,
     client: Client,
+    ip_version: IpVersion,
+    connect_address: Option<SocketAddr>,
+    tracer: Option<Tracer>,
+    setup_time: Cell<Duration>,
+    response_sizes: RefCell<Vec<u64>>,
+    read_timeout: Duration,
 }
 
+/// WS read timeout used until [`HttpTransport::with_read_timeout`] sets one.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
 impl HttpTransport {
     pub fn new(base_url: impl Into<String>) -> Result<Self, TransportError> {
         let normalized = normalize_base_url(base_url.into())?;
@@ -27,8 +184,221 @@ impl HttpTransport {
         Ok(Self {
             base_url: normalized,
             client,
+            ip_version: IpVersion::Auto,
+            connect_address: None,
+            tracer: None,
+            setup_time: Cell::new(Duration::ZERO),
+            response_sizes: RefCell::new(Vec::new()),
+            read_timeout: DEFAULT_READ_TIMEOUT,
         })
     }
+
+    /// Pins every HTTP and WS connection to one address of the target host.
+    ///
+    /// An explicit `address` wins; otherwise the first resolved address of `ip_version` is used.
+    /// `Auto` without an address keeps the default resolver and connector behavior.
+    pub fn with_network(
+        mut self,
+        ip_version: IpVersion,
+        address: Option<IpAddr>,
+    ) -> Result<Self, TransportError> {
+        let (host, port) = self.target_host_port()?;
+        let address = match address {
+            Some(address) if !ip_version.matches(address) => {
+                return Err(TransportError::Protocol(format!(
+                    "pinned address {address} is not an IPv{ip_version} address"
+                )));
+            }
+            Some(address) => address,
+            None if ip_version == IpVersion::Auto => return Ok(self),
+            None => self
+                .resolve_target()?
+                .into_iter()
+                .find(|address| ip_version.matches(*address))
+                .ok_or_else(|| {
+                    TransportError::Http(format!("{host} has no IPv{ip_version} address"))
+                })?,
+        };
+        ensure_reachable_via(&host, address)?;
+
+        let connect_address = SocketAddr::new(address, port);
+        self.client = Client::builder()
+            .resolve(&host, connect_address)
+            .build()
+            .map_err(|error| TransportError::Http(error.to_string()))?;
+        self.ip_version = ip_version;
+        self.connect_address = Some(connect_address);
+        Ok(self)
+    }
+
+    /// Longest a WS handshake or frame read may block before the exchange fails.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Attaches `traceparent`/`tracestate` to every HTTP request and WS handshake.
+    ///
+    /// `trace.ws_frames` only applies when the config claims the `ws-frame-trace` profile.
+    pub fn with_trace(mut self, config: &ConformanceConfig) -> Result<Self, TransportError> {
+        self.tracer = if config.trace.enabled {
+            Some(Tracer::new(
+                &config.trace,
+                config.has_profile(FRAME_TRACE_PROFILE),
+            )?)
+        } else {
+            None
+        };
+        Ok(self)
+    }
+
+    pub fn trace_id(&self) -> Option<&str> {
+        self.tracer.as_ref().map(Tracer::trace_id)
+    }
+
+    pub fn ip_version(&self) -> IpVersion {
+        self.ip_version
+    }
+
+    /// Address every connection is pinned to, if any.
+    pub fn connect_address(&self) -> Option<SocketAddr> {
+        self.connect_address
+    }
+
+    fn trace_headers(&self) -> Vec<(&'static str, String)> {
+        self.tracer
+            .as_ref()
+            .map(Tracer::headers)
+            .unwrap_or_default()
+    }
+
+    fn traced(&self, request: &RawRequest) -> RawRequest {
+        let mut request = request.clone();
+        for (name, value) in self.trace_headers() {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    fn frame(&self, frame: &Value) -> Value {
+        match &self.tracer {
+            Some(tracer) => tracer.annotate_frame(frame),
+            None => frame.clone(),
+        }
+    }
+
+    fn open_websocket(&self) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, TransportError> {
+        let mut request = websocket_url(&self.base_url)
+            .into_client_request()
+            .map_err(|error| TransportError::Protocol(format!("invalid websocket URL: {error}")))?;
+        for (name, value) in self.trace_headers() {
+            let value = HeaderValue::from_str(&value).map_err(|error| {
+                TransportError::Protocol(format!("invalid {name} header: {error}"))
+            })?;
+            request.headers_mut().insert(name, value);
+        }
+        let started = Instant::now();
+        let stream = match self.connect_address {
+            Some(address) => TcpStream::connect(address),
+            None => {
+                let (host, port) = self.target_host_port()?;
+                TcpStream::connect((host.as_str(), port))
+            }
+        }
+        .map_err(|error| TransportError::Http(format!("websocket connect failed: {error}")))?;
+        stream
+            .set_read_timeout(Some(self.read_timeout))
+            .map_err(|error| TransportError::Http(error.to_string()))?;
+        let socket = client_tls(request, stream)
+            .map_err(|error| TransportError::Http(format!("websocket connect failed: {error}")))?
+            .0;
+        self.setup_time
+            .set(self.setup_time.get() + started.elapsed());
+        Ok(socket)
+    }
+
+    /// Sends a HEAD request over its own connection and keeps any bytes that follow the
+    /// response headers. HTTP clients discard HEAD response bodies, so only a raw read can
+    /// show whether the gateway sent one.
+    fn head_raw(
+        &self,
+        address: Option<SocketAddr>,
+        request: &RawRequest,
+    ) -> Result<RawResponse, TransportError> {
+        let url = Url::parse(&self.base_url)
+            .map_err(|error| TransportError::Protocol(format!("invalid base URL: {error}")))?;
+        let (host, port) = self.target_host_port()?;
+        let stream = match address {
+            Some(address) => TcpStream::connect_timeout(&address, RAW_READ_TIMEOUT),
+            None => TcpStream::connect((host.as_str(), port)),
+        }
+        .map_err(|error| TransportError::Http(format!("connect to {host} failed: {error}")))?;
+        stream
+            .set_read_timeout(Some(RAW_READ_TIMEOUT))
+            .map_err(|error| TransportError::Http(error.to_string()))?;
+
+        let host_header = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or(&host)),
+            None => url.host_str().unwrap_or(&host).to_owned(),
+        };
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {host_header}\r\nConnection: close\r\n",
+            request.method,
+            normalize_path(&request.path)
+        );
+        for (name, value) in &request.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+
+        let bytes = if url.scheme() == "https" {
+            let name = ServerName::try_from(host.clone())
+                .map_err(|error| TransportError::Protocol(format!("invalid TLS name: {error}")))?;
+            let connection = ClientConnection::new(tls_config(), name)
+                .map_err(|error| TransportError::Http(format!("TLS setup failed: {error}")))?;
+            raw_exchange(StreamOwned::new(connection, stream), head.as_bytes())?
+        } else {
+            raw_exchange(stream, head.as_bytes())?
+        };
+        parse_raw_response(&bytes)
+    }
+
+    fn target_host_port(&self) -> Result<(String, u16), TransportError> {
+        let url = Url::parse(&self.base_url)
+            .map_err(|error| TransportError::Protocol(format!("invalid base URL: {error}")))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| TransportError::Protocol("base URL has no host".to_owned()))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_owned();
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| TransportError::Protocol("base URL has no port".to_owned()))?;
+        Ok((host, port))
+    }
+
+    fn read_json_body(
+        &self,
+        response: reqwest::blocking::Response,
+    ) -> Result<Value, TransportError> {
+        let body = response
+            .bytes()
+            .map_err(|error| TransportError::Http(error.to_string()))?;
+        let payload = serde_json::from_slice(&body)
+            .map_err(|error| TransportError::Protocol(error.to_string()))?;
+        self.response_sizes.borrow_mut().push(body.len() as u64);
+        Ok(payload)
+    }
+
+    fn read_ws_response(
+        &self,
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    ) -> Result<Value, TransportError> {
+        let (payload, bytes) = read_ws_json(socket)?;
+        self.response_sizes.borrow_mut().push(bytes);
+        Ok(payload)
+    }
 }
 
 impl ConformanceTransport for HttpTransport {
@@ -36,50 +406,47 @@ impl ConformanceTransport for HttpTransport {
         let path = normalize_path(path);
         let url = format!("{}{}", self.base_url, path);
 
-        let response = self
-            .client
-            .get(&url)
+        let mut builder = self.client.get(&url);
+        for (name, value) in self.trace_headers() {
+            builder = builder.header(name, value);
+        }
+        let response = builder
             .send()
             .map_err(|error| TransportError::Http(error.to_string()))?;
 
         if response.status() != StatusCode::OK {
-            return Err(TransportError::Protocol(format!(
-                "unexpected status {} for {path}",
-                response.status()
-            )));
+            return Err(TransportError::Status {
+                status: response.status().as_u16(),
+                path,
+            });
         }
 
-        response
-            .json::<Value>()
-            .map_err(|error| TransportError::Protocol(error.to_string()))
+        self.read_json_body(response)
     }
 
     fn post_json(&self, path: &str, body: &Value) -> Result<(u16, Value), TransportError> {
         let path = normalize_path(path);
         let url = format!("{}{}", self.base_url, path);
 
-        let response = self
-            .client
-            .post(&url)
-            .json(body)
+        let mut builder = self.client.post(&url).json(body);
+        for (name, value) in self.trace_headers() {
+            builder = builder.header(name, value);
+        }
+        let response = builder
             .send()
             .map_err(|error| TransportError::Http(error.to_string()))?;
 
         let status = u16::from(response.status());
-        let payload = response
-            .json::<Value>()
-            .map_err(|error| TransportError::Protocol(error.to_string()))?;
+        let payload = self.read_json_body(response)?;
 
         Ok((status, payload))
     }
 
     fn websocket_first_response(&self, frame: &Value) -> Result<Value, TransportError> {
-        let ws_url = websocket_url(&self.base_url);
-        let (mut socket, _) = connect(ws_url.as_str())
-            .map_err(|error| TransportError::Http(format!("websocket connect failed: {error}")))?;
+        let mut socket = self.open_websocket()?;
 
-        send_ws_json(&mut socket, frame)?;
-        read_ws_json(&mut socket)
+        send_ws_json(&mut socket, &self.frame(frame))?;
+        self.read_ws_response(&mut socket)
     }
 
     fn websocket_exchange(&self, frames: &[Value]) -> Result<Vec<Value>, TransportError> {
@@ -89,18 +456,223 @@ impl ConformanceTransport for HttpTransport {
             ));
         }
 
-        let ws_url = websocket_url(&self.base_url);
-        let (mut socket, _) = connect(ws_url.as_str())
-            .map_err(|error| TransportError::Http(format!("websocket connect failed: {error}")))?;
+        let mut socket = self.open_websocket()?;
 
         let mut responses = Vec::with_capacity(frames.len());
         for frame in frames {
-            send_ws_json(&mut socket, frame)?;
-            responses.push(read_ws_json(&mut socket)?);
+            send_ws_json(&mut socket, &self.frame(frame))?;
+            responses.push(self.read_ws_response(&mut socket)?);
         }
 
         Ok(responses)
     }
+
+    fn request_raw(&self, request: &RawRequest) -> Result<RawResponse, TransportError> {
+        if request.method.eq_ignore_ascii_case("HEAD") {
+            return self.head_raw(self.connect_address, &self.traced(request));
+        }
+        send_raw(&self.client, &self.base_url, &self.traced(request))
+    }
+
+    /// Covers WebSocket connect and handshake; pooled HTTP connections are not separated.
+    fn connection_setup_time(&self) -> Duration {
+        self.setup_time.get()
+    }
+
+    fn take_response_sizes(&self) -> Option<Vec<u64>> {
+        Some(self.response_sizes.take())
+    }
+
+    fn resolve_target(&self) -> Result<Vec<IpAddr>, TransportError> {
+        let (host, port) = self.target_host_port()?;
+        let resolved = (host.as_str(), port)
+            .to_socket_addrs()
+            .map_err(|error| TransportError::Http(format!("failed to resolve {host}: {error}")))?;
+
+        let mut addresses = Vec::new();
+        for address in resolved {
+            if !addresses.contains(&address.ip()) {
+                addresses.push(address.ip());
+            }
+        }
+        Ok(addresses)
+    }
+
+    fn request_raw_via(
+        &self,
+        address: IpAddr,
+        request: &RawRequest,
+    ) -> Result<RawResponse, TransportError> {
+        let (host, port) = self.target_host_port()?;
+        ensure_reachable_via(&host, address)?;
+        if request.method.eq_ignore_ascii_case("HEAD") {
+            return self.head_raw(Some(SocketAddr::new(address, port)), &self.traced(request));
+        }
+        let client = Client::builder()
+            .resolve(&host, SocketAddr::new(address, port))
+            .build()
+            .map_err(|error| TransportError::Http(error.to_string()))?;
+        send_raw(&client, &self.base_url, &self.traced(request))
+    }
+}
+
+fn ensure_reachable_via(host: &str, address: IpAddr) -> Result<(), TransportError> {
+    match host.parse::<IpAddr>() {
+        Ok(literal) if literal != address => Err(TransportError::Protocol(format!(
+            "base URL host {literal} cannot be reached via {address}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn send_raw(
+    client: &Client,
+    base_url: &str,
+    request: &RawRequest,
+) -> Result<RawResponse, TransportError> {
+    let path = normalize_path(&request.path);
+    let url = format!("{base_url}{path}");
+    let method = Method::from_bytes(request.method.as_bytes()).map_err(|error| {
+        TransportError::Protocol(format!("invalid HTTP method {}: {error}", request.method))
+    })?;
+
+    let mut builder = client.request(method, &url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    if let Some(body) = &request.body {
+        builder = builder.body(body.clone());
+    }
+    let response = builder
+        .send()
+        .map_err(|error| TransportError::Http(error.to_string()))?;
+
+    let status = u16::from(response.status());
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_owned(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    let body = response
+        .bytes()
+        .map_err(|error| TransportError::Http(error.to_string()))?
+        .to_vec();
+
+    Ok(RawResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Read timeout for raw HTTP exchanges; once the headers are in, bytes that arrive within
+/// `RAW_TRAILING_WAIT` are treated as a body.
+const RAW_READ_TIMEOUT: Duration = Duration::from_secs(10);
+const RAW_TRAILING_WAIT: Duration = Duration::from_millis(250);
+
+fn tls_config() -> Arc<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    Arc::new(
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("ring provider supports the default protocol versions")
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    )
+}
+
+/// Writes `request` and reads until the peer closes, or until it stays silent for
+/// `RAW_TRAILING_WAIT` after the response headers.
+fn raw_exchange<S>(mut stream: S, request: &[u8]) -> Result<Vec<u8>, TransportError>
+where
+    S: Read + Write + RawSocket,
+{
+    stream
+        .write_all(request)
+        .and_then(|()| stream.flush())
+        .map_err(|error| TransportError::Http(format!("raw request failed: {error}")))?;
+
+    let mut bytes = Vec::new();
+    let mut buffer = [0_u8; 4096];
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => {
+                bytes.extend_from_slice(&buffer[..read]);
+                if header_end(&bytes).is_some() {
+                    stream.set_timeout(RAW_TRAILING_WAIT)?;
+                }
+            }
+            Err(error)
+                if header_end(&bytes).is_some()
+                    && matches!(
+                        error.kind(),
+                        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::UnexpectedEof
+                    ) =>
+            {
+                break
+            }
+            Err(error) => {
+                return Err(TransportError::Http(format!(
+                    "raw response read failed: {error}"
+                )))
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+/// Stream whose underlying socket read timeout can be shortened mid-exchange.
+trait RawSocket {
+    fn set_timeout(&self, timeout: Duration) -> Result<(), TransportError>;
+}
+
+impl RawSocket for TcpStream {
+    fn set_timeout(&self, timeout: Duration) -> Result<(), TransportError> {
+        self.set_read_timeout(Some(timeout))
+            .map_err(|error| TransportError::Http(error.to_string()))
+    }
+}
+
+impl RawSocket for StreamOwned<ClientConnection, TcpStream> {
+    fn set_timeout(&self, timeout: Duration) -> Result<(), TransportError> {
+        self.sock.set_timeout(timeout)
+    }
+}
+
+fn header_end(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|position| position + 4)
+}
+
+fn parse_raw_response(bytes: &[u8]) -> Result<RawResponse, TransportError> {
+    let end = header_end(bytes).ok_or_else(|| {
+        TransportError::Protocol("raw response ended before its headers".to_owned())
+    })?;
+    let head = String::from_utf8_lossy(&bytes[..end]);
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| TransportError::Protocol("raw response has no status line".to_owned()))?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+        .collect();
+    Ok(RawResponse {
+        status,
+        headers,
+        body: bytes[end..].to_vec(),
+    })
 }
 
 fn normalize_base_url(input: String) -> Result<String, TransportError> {
@@ -151,19 +723,26 @@ fn send_ws_json(
         .map_err(|error| TransportError::Http(format!("websocket send failed: {error}")))
 }
 
+/// Next JSON text frame and its size in bytes.
 fn read_ws_json(
     socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
-) -> Result<Value, TransportError> {
+) -> Result<(Value, u64), TransportError> {
     loop {
-        let message = socket
-            .read()
-            .map_err(|error| TransportError::Http(format!("websocket read failed: {error}")))?;
+        let message = socket.read().map_err(|error| match error {
+            tungstenite::Error::Io(error)
+                if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                TransportError::Http("websocket read timed out".to_owned())
+            }
+            error => TransportError::Http(format!("websocket read failed: {error}")),
+        })?;
 
         match message {
             Message::Text(text) => {
-                return serde_json::from_str(text.as_ref()).map_err(|error| {
+                let payload = serde_json::from_str(text.as_ref()).map_err(|error| {
                     TransportError::Protocol(format!("invalid websocket frame JSON: {error}"))
-                });
+                })?;
+                return Ok((payload, text.len() as u64));
             }
             Message::Ping(payload) => {
                 socket.send(Message::Pong(payload)).map_err(|error| {
@@ -193,6 +772,9 @@ pub enum TransportError {
 
     #[error("transport protocol error: {0}")]
     Protocol(String),
+
+    #[error("unexpected status {status} for {path}")]
+    Status { status: u16, path: String },
 }
 
 #[cfg(test)]
@@ -200,13 +782,19 @@ mod tests {
     use std::{
         io::{Read, Write},
         net::TcpListener,
+        sync::mpsc,
         thread,
+        time::{Duration, Instant},
     };
 
     use serde_json::json;
     use tungstenite::{accept, Message};
 
-    use crate::transport::{websocket_url, ConformanceTransport, HttpTransport};
+    use crate::{
+        config::TraceConfig,
+        transport::{websocket_url, ConformanceTransport, HttpTransport, IpVersion, RawRequest},
+        ConformanceConfig,
+    };
 
     use crate::transport::normalize_base_url;
 
@@ -262,6 +850,105 @@ mod tests {
         let _ = server.join();
     }
 
+    #[test]
+    fn get_json_reports_wire_size_of_response_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener should expose local addr");
+
+        let body = "{ \"ok\": true }\n";
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("connection should arrive");
+            let mut buffer = [0_u8; 4096];
+            let _ = stream
+                .read(&mut buffer)
+                .expect("request should be readable");
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .expect("response should be writable");
+        });
+
+        let transport =
+            HttpTransport::new(format!("http://{addr}")).expect("transport should construct");
+        let payload = transport
+            .get_json("/healthz")
+            .expect("request should succeed");
+
+        assert_eq!(payload, json!({ "ok": true }));
+        assert_eq!(
+            transport.take_response_sizes(),
+            Some(vec![body.len() as u64])
+        );
+        assert_eq!(transport.take_response_sizes(), Some(Vec::new()));
+        let _ = server.join();
+    }
+
+    #[test]
+    fn head_request_keeps_body_bytes_sent_after_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener should expose local addr");
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("connection should arrive");
+            let mut buffer = [0_u8; 4096];
+            let read = stream
+                .read(&mut buffer)
+                .expect("request should be readable");
+            assert!(buffer[..read].starts_with(b"HEAD /healthz HTTP/1.1\r\n"));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 11\r\n\r\n{\"ok\":true}")
+                .expect("response should be writable");
+        });
+
+        let transport =
+            HttpTransport::new(format!("http://{addr}")).expect("transport should construct");
+        let response = transport
+            .request_raw(&RawRequest::new("HEAD", "/healthz"))
+            .expect("request should succeed");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.len(), 11);
+        let _ = server.join();
+    }
+
+    #[test]
+    fn websocket_exchange_times_out_when_gateway_never_replies() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener should expose local addr");
+        let (done, finished) = mpsc::channel::<()>();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("connection should arrive");
+            let mut socket = accept(stream).expect("handshake should complete");
+            let _ = socket.read().expect("connect frame should arrive");
+            let _ = finished.recv();
+        });
+
+        let transport = HttpTransport::new(format!("http://{addr}"))
+            .expect("transport should construct")
+            .with_read_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let error = transport
+            .websocket_exchange(&[json!({ "type": "req", "id": "c", "method": "connect" })])
+            .expect_err("silent gateway should time out");
+
+        assert!(error.to_string().contains("timed out"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let _ = done.send(());
+        let _ = server.join();
+    }
+
     #[test]
     fn websocket_url_maps_http_scheme_to_ws() {
         assert_eq!(
@@ -319,4 +1006,199 @@ mod tests {
         assert_eq!(response["error"]["code"], "INVALID_REQUEST");
         let _ = server.join();
     }
+
+    #[test]
+    fn request_raw_sends_method_headers_and_body_verbatim() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener should expose local addr");
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("connection should arrive");
+            let mut buffer = [0_u8; 4096];
+            let read = stream
+                .read(&mut buffer)
+                .expect("request should be readable");
+            let request = String::from_utf8_lossy(&buffer[..read]).into_owned();
+
+            let body = "nope";
+            let response = format!(
+                "HTTP/1.1 415 Unsupported Media Type\r\nContent-Type: text/plain\r\nX-Trace: abc\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .expect("response should be writable");
+            request
+        });
+
+        let transport =
+            HttpTransport::new(format!("http://{addr}")).expect("transport should construct");
+        let response = transport
+            .request_raw(
+                &RawRequest::new("POST", "/tools/invoke")
+                    .header("Content-Type", "text/plain")
+                    .body("{}"),
+            )
+            .expect("request should succeed");
+        let request = server.join().expect("server should finish");
+
+        assert!(request.starts_with("POST /tools/invoke HTTP/1.1\r\n"));
+        assert!(request.contains("content-type: text/plain\r\n"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+        assert_eq!(response.status, 415);
+        assert_eq!(response.header("x-trace"), Some("abc"));
+        assert_eq!(response.body, b"nope");
+    }
+
+    #[test]
+    fn request_raw_via_connects_to_given_address_with_original_host() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let port = listener
+            .local_addr()
+            .expect("listener should expose local addr")
+            .port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("connection should arrive");
+            let mut buffer = [0_u8; 4096];
+            let read = stream
+                .read(&mut buffer)
+                .expect("request should be readable");
+            let request = String::from_utf8_lossy(&buffer[..read]).into_owned();
+
+            let body = r#"{"ok":true}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .expect("response should be writable");
+            request
+        });
+
+        let transport = HttpTransport::new(format!("http://gateway.invalid:{port}"))
+            .expect("transport should construct");
+        let response = transport
+            .request_raw_via(
+                "127.0.0.1".parse().expect("IPv4 literal"),
+                &RawRequest::new("GET", "/healthz"),
+            )
+            .expect("request should succeed");
+        let request = server.join().expect("server should finish");
+
+        assert!(request.contains(&format!("host: gateway.invalid:{port}\r\n")));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, br#"{"ok":true}"#);
+    }
+
+    #[test]
+    fn with_network_pins_websocket_connections_to_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let port = listener
+            .local_addr()
+            .expect("listener should expose local addr")
+            .port();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("connection should arrive");
+            let mut ws = accept(stream).expect("websocket handshake should succeed");
+            let _ = ws.read().expect("request frame should arrive");
+            ws.send(Message::Text(json!({ "ok": true }).to_string().into()))
+                .expect("response should be sent");
+        });
+
+        let transport = HttpTransport::new(format!("http://gateway.invalid:{port}"))
+            .and_then(|transport| {
+                transport.with_network(IpVersion::V4, Some("127.0.0.1".parse().expect("IPv4")))
+            })
+            .expect("transport should pin");
+        assert_eq!(
+            transport
+                .connect_address()
+                .map(|address| address.to_string()),
+            Some(format!("127.0.0.1:{port}"))
+        );
+
+        let response = transport
+            .websocket_first_response(&json!({ "method": "health" }))
+            .expect("response should be received");
+        assert_eq!(response["ok"], true);
+        let _ = server.join();
+    }
+
+    #[test]
+    fn with_network_rejects_address_outside_ip_version() {
+        let error = HttpTransport::new("http://gateway.invalid:18789")
+            .and_then(|transport| {
+                transport.with_network(IpVersion::V6, Some("127.0.0.1".parse().expect("IPv4")))
+            })
+            .err()
+            .expect("pinning should fail");
+        assert_eq!(
+            error.to_string(),
+            "transport protocol error: pinned address 127.0.0.1 is not an IPv6 address"
+        );
+        assert_eq!("auto".parse::<IpVersion>(), Ok(IpVersion::Auto));
+        assert!("5".parse::<IpVersion>().is_err());
+    }
+
+    #[test]
+    fn with_trace_attaches_trace_context_headers_and_frame_field() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener should expose local addr");
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("connection should arrive");
+            let mut buffer = [0_u8; 4096];
+            let read = stream
+                .peek(&mut buffer)
+                .expect("handshake should be readable");
+            let handshake = String::from_utf8_lossy(&buffer[..read]).into_owned();
+            let mut ws = accept(stream).expect("websocket handshake should succeed");
+            let request = ws.read().expect("request frame should arrive");
+            let text = request.into_text().expect("request frame should be text");
+            let frame: serde_json::Value =
+                serde_json::from_str(text.as_ref()).expect("frame JSON should parse");
+            ws.send(Message::Text(json!({ "ok": true }).to_string().into()))
+                .expect("response should be sent");
+            (handshake, frame)
+        });
+
+        let transport = HttpTransport::new(format!("http://{addr}"))
+            .and_then(|transport| {
+                transport.with_trace(&ConformanceConfig {
+                    profiles: vec!["ws-frame-trace".to_owned()],
+                    trace: TraceConfig {
+                        enabled: true,
+                        parent: Some(
+                            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_owned(),
+                        ),
+                        tracestate: None,
+                        ws_frames: true,
+                    },
+                    ..ConformanceConfig::default()
+                })
+            })
+            .expect("transport should trace");
+        assert_eq!(
+            transport.trace_id(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        transport
+            .websocket_first_response(&json!({ "method": "health" }))
+            .expect("response should be received");
+        let (handshake, frame) = server.join().expect("server should finish");
+
+        assert!(handshake.contains("traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(frame["trace"]["traceparent"]
+            .as_str()
+            .is_some_and(|value| value.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-")));
+    }
 }